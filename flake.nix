@@ -44,7 +44,17 @@
 
               buildInputs = with pkgs; [ openssl ] ++ lib.optional stdenv.isDarwin
                 (with darwin.apple_sdk.frameworks; [ CoreFoundation Security ]);
-              nativeBuildInputs = with pkgs; [ pkg-config ];
+              nativeBuildInputs = with pkgs; [ pkg-config installShellFiles ];
+
+              postInstall = lib.optionalString (pkgs.stdenv.buildPlatform.canExecute pkgs.stdenv.hostPlatform) ''
+                installShellCompletion --cmd lure \
+                  --bash <($out/bin/lure completions bash) \
+                  --fish <($out/bin/lure completions fish) \
+                  --zsh <($out/bin/lure completions zsh)
+
+                $out/bin/lure manpage --out-dir man
+                installManPage man/*.1
+              '';
 
               meta = {
                 inherit (cargoTOML.package) description license;
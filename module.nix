@@ -13,6 +13,12 @@ let
 
   supportedServices = [ "lastfm" "listenbrainz" ];
 
+  # The shortest check interval of each service in seconds, to stay in its
+  # rate limits, and the longest one of all services. Keep in sync with
+  # `src/config/mod.rs` and `src/config/interval.rs`.
+  minimumCheckIntervals = { lastfm = 5; listenbrainz = 1; };
+  maximumCheckInterval = 60 * 60;
+
   commonServiceOptions = service: {
     username = mkOption {
       type = types.str;
@@ -20,8 +26,8 @@ let
     };
 
     check_interval = mkOption {
-      type = types.int;
-      description = "Interval in seconds to check for listening activity.";
+      type = types.either (types.ints.between minimumCheckIntervals.${service} maximumCheckInterval) types.str;
+      description = "Interval to check for listening activity, in seconds (${toString minimumCheckIntervals.${service}} to ${toString maximumCheckInterval}) or as a duration such as `\"15s\"` or `\"2m\"`. Durations are only checked when lure starts.";
       default = 16;
     };
   };
@@ -95,6 +101,7 @@ in
             The following placeholders can be used:
             - %NAME%: The name of the song.
             - %ARTIST%: The artist of the song.
+            - %ALBUM%: The album of the song, if known.
           '';
           default = "🎵 Listening to %NAME% by %ARTIST%";
         };
@@ -109,6 +116,36 @@ in
           '';
           default = null;
         };
+
+        max_length = mkOption {
+          type = types.ints.between 1 128;
+          description = ''
+            The maximum length of the status text.
+
+            If the rendered status is longer than this, the song name is
+            shortened first, then the artist.
+          '';
+          default = 128;
+        };
+
+        ellipsis = mkOption {
+          type = types.str;
+          description = "The text to append to shortened fields.";
+          default = "…";
+        };
+
+        presence =
+          let
+            presenceOption = description: mkOption {
+              type = types.nullOr (types.enum [ "online" "idle" "focus" "busy" "invisible" ]);
+              inherit description;
+              default = null;
+            };
+          in
+          {
+            listening = presenceOption "The presence to set while listening to something.";
+            idle = presenceOption "The presence to set while not listening to anything.";
+          };
       };
 
       api_url = mkOption {
@@ -117,8 +154,99 @@ in
         default = "https://api.revolt.chat";
       };
 
+      artwork = mkOption {
+        type = types.nullOr (types.enum [ "background" "avatar" ]);
+        description = ''
+          Where to show the cover art of the current track.
+
+          The original is restored when not listening to anything,
+          and when lure stops.
+        '';
+        default = null;
+      };
+
+      channel = mkOption {
+        type = types.nullOr (types.submodule {
+          options = {
+            id = mkOption {
+              type = types.str;
+              description = "The ID of the channel.";
+            };
+
+            mode = mkOption {
+              type = types.enum [ "send" "edit" ];
+              description = "Whether to send a new message for every track, or to keep editing a single message.";
+              default = "send";
+            };
+
+            message_id = mkOption {
+              type = types.nullOr types.str;
+              description = ''
+                The ID of the message to edit, such as a pinned message.
+
+                If this option is not set in `edit` mode, a new message is
+                sent for the first track and edited afterwards.
+              '';
+              default = null;
+            };
+          };
+        });
+        description = "Channel to post the current track to.";
+        default = null;
+      };
+
+      profile = mkOption {
+        type = types.nullOr (types.submodule {
+          options = {
+            template = mkOption {
+              type = types.str;
+              description = "The now playing template. The same placeholders as the status template can be used.";
+              default = "🎵 Now playing: **%NAME%** by %ARTIST%";
+            };
+
+            idle = mkOption {
+              type = types.nullOr types.str;
+              description = "The text to show when not listening to anything.";
+              default = null;
+            };
+
+            recent_tracks = mkOption {
+              type = types.ints.unsigned;
+              description = "How many recently played tracks to show under the now playing line.";
+              default = 0;
+            };
+
+            recent_track_template = mkOption {
+              type = types.str;
+              description = "The template for each recently played track.";
+              default = "- %NAME% by %ARTIST%";
+            };
+
+            start_marker = mkOption {
+              type = types.str;
+              description = "The line that marks the start of the now playing section.";
+              default = "[](#lure-start)";
+            };
+
+            end_marker = mkOption {
+              type = types.str;
+              description = "The line that marks the end of the now playing section.";
+              default = "[](#lure-end)";
+            };
+          };
+        });
+        description = ''
+          Profile bio options.
+
+          A now playing section is kept in your profile bio, between the
+          start and end markers. The rest of the bio is left as is, and
+          the original bio is restored when lure stops.
+        '';
+        default = null;
+      };
+
       session_token = mkOption {
-        type = with types; either str path;
+        type = with types; nullOr (either str path);
         description = ''
           The `X-Session-Token` to use for the API.
 
@@ -126,6 +254,20 @@ in
           it is recommended to use a path to a file that contains the token,
           instead of entering the token as a string, so it's stored securely.
         '';
+        default = null;
+      };
+
+      bot_token = mkOption {
+        type = with types; nullOr (either str path);
+        description = ''
+          The `X-Bot-Token` to use for the API, to set the status of a bot
+          account instead. Cannot be used together with `session_token`.
+
+          WARNING: It is recommended to use a path to a file that contains
+          the token, instead of entering the token as a string, so it's
+          stored securely.
+        '';
+        default = null;
       };
     };
   };
@@ -134,6 +276,9 @@ in
     let
       lastfmServiceEnabled = cfg.useService == "lastfm" && cfg.services.lastfm != null;
       listenbrainzServiceEnabled = cfg.useService == "listenbrainz" && cfg.services.listenbrainz != null;
+
+      revoltTokenType = if cfg.revolt.bot_token != null then "bot_token" else "session_token";
+      revoltToken = cfg.revolt.${revoltTokenType};
     in
     mkIf (cfg.enable && cfg.useService != null) {
       assertions = [
@@ -145,10 +290,14 @@ in
           assertion = cfg.useService == "listenbrainz" -> cfg.services.listenbrainz != null;
           message = "'services.listenbrainz' options must be provided when using ListenBrainz service.";
         }
+        {
+          assertion = (cfg.revolt.session_token == null) != (cfg.revolt.bot_token == null);
+          message = "Exactly one of 'revolt.session_token' and 'revolt.bot_token' must be provided.";
+        }
       ];
 
       warnings = [ ]
-        ++ optional (isString cfg.revolt.session_token) "'revolt.session_token' is specified as a string, PLEASE consider using a path to a file instead for the sake of security."
+        ++ optional (isString revoltToken) "'revolt.${revoltTokenType}' is specified as a string, PLEASE consider using a path to a file instead for the sake of security."
         ++ optional (lastfmServiceEnabled && isString cfg.services.lastfm.api_key) "'services.lastfm.api_key' is specified as a string, PLEASE consider using a path to a file instead for the sake of security.";
 
       systemd.services.lure = {
@@ -169,7 +318,7 @@ in
             let
               credentials = [ ]
                 ++ optional (lastfmServiceEnabled && isPath cfg.services.lastfm.api_key) "lastfm-api-key:${cfg.services.lastfm.api_key}"
-                ++ optional (isPath cfg.revolt.session_token) "revolt-session-token:${cfg.revolt.session_token}";
+                ++ optional (isPath revoltToken) "revolt-token:${revoltToken}";
             in
             credentials;
         };
@@ -180,6 +329,8 @@ in
             LURE_ENABLE = cfg.useService;
 
             LURE_REVOLT__STATUS__TEMPLATE = escapePercentLiteral cfg.revolt.status.template;
+            LURE_REVOLT__STATUS__MAX_LENGTH = toString cfg.revolt.status.max_length;
+            LURE_REVOLT__STATUS__ELLIPSIS = escapePercentLiteral cfg.revolt.status.ellipsis;
             LURE_REVOLT__API_URL = cfg.revolt.api_url;
           }
           (optionalAttrs lastfmServiceEnabled (mkMerge [
@@ -191,7 +342,7 @@ in
               LURE_SERVICES__LASTFM__API_KEY = cfg.services.lastfm.api_key;
             })
             (optionalAttrs (isPath cfg.services.lastfm.api_key) {
-              LURE_SERVICES__LASTFM__API_KEY_FILE = "%d/lastfm-api-key";
+              LURE_SERVICES__LASTFM__API_KEY_CREDENTIAL = "lastfm-api-key";
             })
           ]))
           (optionalAttrs listenbrainzServiceEnabled {
@@ -202,11 +353,44 @@ in
           (optionalAttrs (cfg.revolt.status.idle != null) {
             LURE_REVOLT__STATUS__IDLE = escapePercentLiteral cfg.revolt.status.idle;
           })
-          (optionalAttrs (isString cfg.revolt.session_token) {
-            LURE_REVOLT__SESSION_TOKEN = cfg.revolt.session_token;
+          (optionalAttrs (cfg.revolt.artwork != null) {
+            LURE_REVOLT__ARTWORK = cfg.revolt.artwork;
+          })
+          (optionalAttrs (cfg.revolt.channel != null) (mkMerge [
+            {
+              LURE_REVOLT__CHANNEL__ID = cfg.revolt.channel.id;
+              LURE_REVOLT__CHANNEL__MODE = cfg.revolt.channel.mode;
+            }
+            (optionalAttrs (cfg.revolt.channel.message_id != null) {
+              LURE_REVOLT__CHANNEL__MESSAGE_ID = cfg.revolt.channel.message_id;
+            })
+          ]))
+          (optionalAttrs (cfg.revolt.profile != null) (mkMerge [
+            {
+              LURE_REVOLT__PROFILE__TEMPLATE = escapePercentLiteral cfg.revolt.profile.template;
+              LURE_REVOLT__PROFILE__RECENT_TRACKS = toString cfg.revolt.profile.recent_tracks;
+              LURE_REVOLT__PROFILE__RECENT_TRACK_TEMPLATE = escapePercentLiteral cfg.revolt.profile.recent_track_template;
+              LURE_REVOLT__PROFILE__START_MARKER = cfg.revolt.profile.start_marker;
+              LURE_REVOLT__PROFILE__END_MARKER = cfg.revolt.profile.end_marker;
+            }
+            (optionalAttrs (cfg.revolt.profile.idle != null) {
+              LURE_REVOLT__PROFILE__IDLE = escapePercentLiteral cfg.revolt.profile.idle;
+            })
+          ]))
+          (optionalAttrs (cfg.revolt.status.presence.listening != null) {
+            LURE_REVOLT__STATUS__PRESENCE__LISTENING = cfg.revolt.status.presence.listening;
+          })
+          (optionalAttrs (cfg.revolt.status.presence.idle != null) {
+            LURE_REVOLT__STATUS__PRESENCE__IDLE = cfg.revolt.status.presence.idle;
+          })
+          (optionalAttrs (isString revoltToken) {
+            LURE_REVOLT__AUTHENTICATION__TYPE = revoltTokenType;
+            "LURE_REVOLT__${toUpper revoltTokenType}" = revoltToken;
           })
-          (optionalAttrs (isPath cfg.revolt.session_token) {
-            LURE_REVOLT__SESSION_TOKEN_FILE = "%d/revolt-session-token";
+          (optionalAttrs (isPath revoltToken) {
+            LURE_REVOLT__AUTHENTICATION__TYPE = "credential";
+            LURE_REVOLT__AUTHENTICATION__NAME = "revolt-token";
+            LURE_REVOLT__AUTHENTICATION__TOKEN_TYPE = revoltTokenType;
           })
         ];
       };
@@ -0,0 +1,24 @@
+use clap::{Args, CommandFactory};
+use clap_complete::Shell;
+use tracing::trace;
+
+use super::{Cli, Command};
+
+#[derive(Args, Debug)]
+pub struct CommandArguments {
+    /// Shell to generate the completions for.
+    #[arg(value_enum)]
+    shell: Shell,
+}
+
+impl Command for CommandArguments {
+    async fn run(&self) -> anyhow::Result<()> {
+        trace!("`completions` subcommand");
+
+        let mut command = Cli::command();
+        let name = command.get_name().to_string();
+        clap_complete::generate(self.shell, &mut command, name, &mut std::io::stdout());
+
+        Ok(())
+    }
+}
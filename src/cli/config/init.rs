@@ -0,0 +1,386 @@
+#![cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+
+use std::{fmt, path::Path};
+
+use inquire::{
+    list_option::ListOption,
+    validator::{Validation, ValueRequiredValidator},
+    Confirm, MultiSelect, Password, Select, Text,
+};
+use rive_models::authentication::Authentication;
+
+use crate::{
+    config::{self, RevoltStatusOptions},
+    revolt,
+    services::{ServiceProvider, TrackInfo},
+    utils::{format::ConfigFormat, yaml},
+};
+
+use super::{login, write_secret_file, GetSessionTokenArguments};
+
+const DEFAULT_REVOLT_API_URL: &str = "https://api.revolt.chat";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ServiceChoice {
+    #[cfg(feature = "services-lastfm")]
+    LastFM,
+    #[cfg(feature = "services-listenbrainz")]
+    ListenBrainz,
+}
+
+impl ServiceChoice {
+    const ALL: &'static [Self] = &[
+        #[cfg(feature = "services-lastfm")]
+        Self::LastFM,
+        #[cfg(feature = "services-listenbrainz")]
+        Self::ListenBrainz,
+    ];
+
+    /// The name used for the service in the config.
+    const fn key(self) -> &'static str {
+        match self {
+            #[cfg(feature = "services-lastfm")]
+            Self::LastFM => "lastfm",
+            #[cfg(feature = "services-listenbrainz")]
+            Self::ListenBrainz => "listenbrainz",
+        }
+    }
+}
+
+impl fmt::Display for ServiceChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            #[cfg(feature = "services-lastfm")]
+            Self::LastFM => "Last.fm",
+            #[cfg(feature = "services-listenbrainz")]
+            Self::ListenBrainz => "ListenBrainz",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum RevoltLoginChoice {
+    Login,
+    SessionToken,
+    BotToken,
+    Skip,
+}
+
+impl fmt::Display for RevoltLoginChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Login => "Log in with e-mail and password now",
+            Self::SessionToken => "Enter an existing session token",
+            Self::BotToken => "Enter a bot token",
+            Self::Skip => "Skip, I'll set it later",
+        })
+    }
+}
+
+/// Asks for the options needed to run lure and writes a minimal config file
+/// to `path`, or to where lure looks for it.
+pub async fn run(path: Option<&Path>) -> anyhow::Result<()> {
+    let path = &path.map_or_else(config::new_config_path, Path::to_path_buf);
+    if path.exists()
+        && !Confirm::new(&format!(
+            "`{}` already exists. Overwrite it?",
+            path.display()
+        ))
+        .with_default(false)
+        .prompt()?
+    {
+        return Ok(());
+    }
+
+    let mut document = String::new();
+
+    let services = match ServiceChoice::ALL {
+        [service] => vec![*service],
+        services => MultiSelect::new("Which services do you use?", services.to_vec())
+            .with_validator(|selected: &[ListOption<&ServiceChoice>]| {
+                Ok(if selected.is_empty() {
+                    Validation::Invalid("Select at least one service.".into())
+                } else {
+                    Validation::Valid
+                })
+            })
+            .prompt()?,
+    };
+    let enabled_service = match services.as_slice() {
+        [service] => *service,
+        services => Select::new(
+            "Which service should lure show in your status?",
+            services.to_vec(),
+        )
+        .prompt()?,
+    };
+    document = yaml::set_value(&document, &["enable"], Some(enabled_service.key()));
+
+    let mut current_track = None;
+    for service in services {
+        println!("\nSetting up {service}.");
+
+        let track = match service {
+            #[cfg(feature = "services-lastfm")]
+            ServiceChoice::LastFM => set_up_lastfm(&mut document).await?,
+            #[cfg(feature = "services-listenbrainz")]
+            ServiceChoice::ListenBrainz => set_up_listenbrainz(&mut document).await?,
+        };
+
+        if service == enabled_service {
+            current_track = track;
+        }
+    }
+
+    println!("\nSetting up Revolt.");
+    set_up_revolt(&mut document).await?;
+    set_up_status_template(&mut document, current_track.as_ref())?;
+
+    // The document is built as YAML, since it can be edited without
+    // parsing it.
+    let document = ConfigFormat::from_path(path).convert_yaml(&document)?;
+
+    if let Some(directory) = path.parent().filter(|directory| !directory.exists()) {
+        std::fs::create_dir_all(directory)?;
+    }
+    // The config contains secrets, so it should only be readable by you.
+    write_secret_file(path, &document)?;
+
+    println!("\nConfig file `{}` is written.", path.display());
+    // Where `lure start` looks without `--config`.
+    let found_path = config::config_path(
+        std::env::var_os("LURE_CONFIG")
+            .map(std::path::PathBuf::from)
+            .as_deref(),
+    );
+    if std::path::absolute(&found_path)? == std::path::absolute(path)? {
+        println!("Run `lure config check --online` to verify it, then `lure start` to start lure.");
+    } else {
+        println!(
+            "lure uses `{}` by default, so give it this file with `--config {}` or `LURE_CONFIG`, such as `lure start --config {1}`.",
+            found_path.display(),
+            path.display()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "services-lastfm")]
+async fn set_up_lastfm(document: &mut String) -> anyhow::Result<Option<TrackInfo>> {
+    loop {
+        let username = Text::new("Last.fm username:")
+            .with_validator(ValueRequiredValidator::default())
+            .prompt()?;
+        let api_key = Password::new("Last.fm API key:")
+            .with_validator(ValueRequiredValidator::default())
+            .with_help_message("You can create one at https://www.last.fm/api/account/create")
+            .without_confirmation()
+            .prompt()?;
+
+        let mut service = crate::services::lastfm::LastFM {
+            options: crate::config::LastFMServiceOptions {
+                username: username.clone(),
+                api_key: api_key.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        service.initialise()?;
+
+        if let Some(track) = test_service(&service).await? {
+            *document = yaml::set_value(
+                document,
+                &["services", "lastfm", "username"],
+                Some(&username),
+            );
+            *document =
+                yaml::set_value(document, &["services", "lastfm", "api_key"], Some(&api_key));
+
+            return Ok(track);
+        }
+    }
+}
+
+#[cfg(feature = "services-listenbrainz")]
+async fn set_up_listenbrainz(document: &mut String) -> anyhow::Result<Option<TrackInfo>> {
+    loop {
+        let username = Text::new("ListenBrainz username:")
+            .with_validator(ValueRequiredValidator::default())
+            .prompt()?;
+
+        let mut service = crate::services::listenbrainz::ListenBrainz {
+            options: crate::config::ListenBrainzServiceOptions {
+                username: username.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        service.initialise()?;
+
+        if let Some(track) = test_service(&service).await? {
+            *document = yaml::set_value(
+                document,
+                &["services", "listenbrainz", "username"],
+                Some(&username),
+            );
+
+            return Ok(track);
+        }
+    }
+}
+
+/// Verifies the service options and fetches the current track. Returns
+/// `None` if the options should be asked for again.
+async fn test_service<S: ServiceProvider>(
+    service: &S,
+) -> anyhow::Result<Option<Option<TrackInfo>>> {
+    if let Err(error) = service.verify().await {
+        println!("The service did not accept the details: {error}");
+
+        return Ok(if Confirm::new("Try again?").with_default(true).prompt()? {
+            None
+        } else {
+            Some(None)
+        });
+    }
+
+    println!("The details are valid.");
+
+    Ok(Some(
+        service.get_current_playing_track().await.ok().flatten(),
+    ))
+}
+
+async fn set_up_revolt(document: &mut String) -> anyhow::Result<()> {
+    let api_url = Text::new("Revolt API URL:")
+        .with_default(DEFAULT_REVOLT_API_URL)
+        .prompt()?;
+    if api_url != DEFAULT_REVOLT_API_URL {
+        *document = yaml::set_value(document, &["revolt", "api_url"], Some(&api_url));
+    }
+
+    let choices = vec![
+        RevoltLoginChoice::Login,
+        RevoltLoginChoice::SessionToken,
+        RevoltLoginChoice::BotToken,
+        RevoltLoginChoice::Skip,
+    ];
+    let authentication = match Select::new("How should lure log in to Revolt?", choices).prompt()? {
+        RevoltLoginChoice::Login => {
+            let arguments = GetSessionTokenArguments {
+                revolt_api_url: api_url.clone(),
+                email: None,
+                password_file: None,
+                password_stdin: false,
+                totp_code: None,
+                recovery_code: None,
+                json: false,
+                write_config: None,
+                write_secret_file: None,
+            };
+
+            login(&arguments).await?.map(Authentication::SessionToken)
+        }
+        RevoltLoginChoice::SessionToken => Some(Authentication::SessionToken(
+            Password::new("Session token:")
+                .with_validator(ValueRequiredValidator::default())
+                .without_confirmation()
+                .prompt()?,
+        )),
+        RevoltLoginChoice::BotToken => Some(Authentication::BotToken(
+            Password::new("Bot token:")
+                .with_validator(ValueRequiredValidator::default())
+                .without_confirmation()
+                .prompt()?,
+        )),
+        RevoltLoginChoice::Skip => None,
+    };
+
+    let Some(authentication) = authentication else {
+        println!("Put your session token to `revolt: session_token` in the config file later.");
+        return Ok(());
+    };
+
+    match revolt::HttpClient::try_new(api_url, &authentication)?
+        .ping()
+        .await
+    {
+        Ok(()) => println!("Revolt accepted the token."),
+        Err(error) => println!("Revolt did not accept the token, but it is saved anyway: {error}"),
+    }
+
+    *document = match &authentication {
+        Authentication::SessionToken(token) => {
+            yaml::set_value(document, &["revolt", "session_token"], Some(token))
+        }
+        Authentication::BotToken(token) => {
+            let document = yaml::set_value(
+                document,
+                &["revolt", "authentication", "type"],
+                Some("bot_token"),
+            );
+            yaml::set_value(&document, &["revolt", "bot_token"], Some(token))
+        }
+        Authentication::ValidMfaTicket(_)
+        | Authentication::UnvalidatedMfaTicket(_)
+        | Authentication::None => unreachable!("only tokens are asked for"),
+    };
+
+    Ok(())
+}
+
+fn set_up_status_template(
+    document: &mut String,
+    current_track: Option<&TrackInfo>,
+) -> anyhow::Result<()> {
+    let example_track = TrackInfo {
+        artist: String::from("Cocteau Twins"),
+        name: String::from("Heaven or Las Vegas"),
+        album: Some(String::from("Heaven or Las Vegas")),
+        cover_art_url: None,
+    };
+    let track = match current_track {
+        Some(track) => {
+            println!("\nPreviewing the status with your current track.");
+            track
+        }
+        None => {
+            println!(
+                "\nNothing is playing right now, so the status is previewed with an example track."
+            );
+            &example_track
+        }
+    };
+
+    let default_template = RevoltStatusOptions::default().template;
+    let mut template = default_template.clone();
+    loop {
+        template = Text::new("Status template:")
+            .with_default(&template)
+            .with_help_message("%ARTIST%, %NAME% and %ALBUM% are replaced with the track")
+            .prompt()?;
+
+        let status_options = RevoltStatusOptions {
+            template: template.clone(),
+            ..Default::default()
+        };
+        println!(
+            "Preview: {}",
+            revolt::status::render(&status_options, track)
+        );
+
+        if Confirm::new("Use this template?")
+            .with_default(true)
+            .prompt()?
+        {
+            break;
+        }
+    }
+
+    if template != default_template {
+        *document = yaml::set_value(document, &["revolt", "status", "template"], Some(&template));
+    }
+
+    Ok(())
+}
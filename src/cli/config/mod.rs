@@ -0,0 +1,944 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use clap::{Args, Subcommand, ValueEnum};
+use inquire::{
+    validator::{Validation, ValueRequiredValidator},
+    CustomUserError, InquireError, Password, Select, Text,
+};
+use regex::Regex;
+use reqwest::StatusCode;
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+use rive_models::authentication::Authentication;
+use rive_models::{
+    data::LoginData,
+    mfa::{MFAData, MFAMethod},
+    session::LoginResponse,
+};
+use serde::{de, Deserialize, Deserializer};
+use tracing::trace;
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+use crate::{config, revolt};
+
+use crate::utils::format::ConfigFormat;
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+use crate::utils::yaml;
+
+use super::Command;
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+use super::ConfigArguments;
+
+mod init;
+mod preview;
+
+const SUCCESSFUL_LOGIN_RESPONSE_TEMPLATE: &str = r#"
+Session token successfully generated. Put this to your configuration file where `revolt: session_token` is.
+
+It should look like this:
+session_token: "{SESSION_TOKEN}"
+
+If you used the `revolt-api-url` option to login to another instance, you should also put that to your configuration file where `revolt: api_url` is.
+
+Important note: Session token allows full access to your account! Never share it with anyone and if possible, store it securely.
+"#;
+
+const SESSION_TOKEN_WARNING: &str = "Important note: Session token allows full access to your account! Never share it with anyone and if possible, store it securely.";
+
+static REVOLT_SESSION_FRIENDLY_NAME: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        "lure on {os}{repo}",
+        os = std::env::consts::OS,
+        repo = env!("CARGO_PKG_REPOSITORY")
+            .strip_prefix("https://")
+            .map_or_else(String::default, |stripped_repo| format!(
+                " ({stripped_repo})"
+            ))
+    )
+});
+
+static EMAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[^@ \t\r\n]+@[^@ \t\r\n]+\.[^@ \t\r\n]+")
+        .expect("e-mail validation regex is somehow invalid now.")
+});
+static RECOVERY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new("^[a-z0-9]{5}-[a-z0-9]{5}$").expect("recovery code regex is somehow invalid now.")
+});
+static TOTP_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new("^[0-9]{6}$").expect("TOTP regex is somehow invalid now."));
+
+type StaticInquireValidatorFn =
+    Box<dyn Fn(&str) -> Result<Validation, CustomUserError> + Sync + Send>;
+
+static INQUIRE_EMAIL_VALIDATOR: LazyLock<StaticInquireValidatorFn> = LazyLock::new(|| {
+    Box::new(|email: &str| {
+        if EMAIL_REGEX.is_match(email) {
+            Ok(Validation::Valid)
+        } else {
+            Ok(Validation::Invalid(
+                "Entered e-mail address is invalid.".into(),
+            ))
+        }
+    })
+});
+static INQUIRE_RECOVERY_VALIDATOR: LazyLock<StaticInquireValidatorFn> = LazyLock::new(|| {
+    Box::new(|code: &str| {
+        if RECOVERY_REGEX.is_match(code) {
+            Ok(Validation::Valid)
+        } else {
+            Ok(Validation::Invalid(
+                "Entered code is not a valid recovery code.".into(),
+            ))
+        }
+    })
+});
+static INQUIRE_TOTP_VALIDATOR: LazyLock<StaticInquireValidatorFn> = LazyLock::new(|| {
+    Box::new(|code: &str| {
+        if TOTP_REGEX.is_match(code) {
+            Ok(Validation::Valid)
+        } else {
+            Ok(Validation::Invalid(
+                "Entered code is not a valid 2FA code.".into(),
+            ))
+        }
+    })
+});
+
+#[derive(Subcommand, Debug)]
+pub enum CommandSubcommands {
+    /// Generate an example lure configuration file and print it.
+    Generate {
+        /// Format of the configuration file. Only YAML keeps the comments
+        /// explaining the options.
+        #[arg(long, value_enum, default_value_t)]
+        format: ConfigFormat,
+    },
+    #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+    /// Create a config file by answering a few questions.
+    Init {
+        /// Path of the config file to create. Its format is detected from
+        /// the extension.
+        ///
+        /// If not given, the config file lure finds is overwritten, or
+        /// `config.yaml` is created in `$XDG_CONFIG_HOME/lure`, which is
+        /// searched first.
+        #[arg(short, long, env = "LURE_CONFIG")]
+        config: Option<PathBuf>,
+    },
+    #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+    /// Check the configuration for problems without starting lure.
+    Check {
+        #[command(flatten)]
+        config: ConfigArguments,
+        /// Also verify the Revolt token and service credentials against
+        /// their APIs.
+        #[arg(long)]
+        online: bool,
+    },
+    #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+    /// Print the configuration lure sees, with the source of every value.
+    ///
+    /// Secrets are redacted.
+    Show {
+        #[command(flatten)]
+        config: ConfigArguments,
+        /// Output format.
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+    /// Render the status template with a given track, or the current one,
+    /// and show how it fits into Revolt's length limit.
+    ///
+    /// The idle status is shown too.
+    PreviewTemplate(preview::PreviewTemplateArguments),
+    #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+    /// Print the JSON Schema of the configuration.
+    ///
+    /// Only the options of the services lure is built with are included.
+    Schema,
+    #[cfg(all(
+        feature = "secrets-age",
+        any(feature = "services-lastfm", feature = "services-listenbrainz")
+    ))]
+    /// Encrypt a secret with age, to put it into the configuration.
+    ///
+    /// The secret is prompted for, unless `--stdin` is given.
+    EncryptSecret {
+        /// Public key of an age recipient to encrypt to, such as
+        /// `age1...`.
+        #[arg(short, long)]
+        recipient: Vec<String>,
+        /// Encrypt to the recipients of an age identity file.
+        #[arg(short, long, value_name = "PATH", env = "LURE_AGE_IDENTITY")]
+        identity: Option<PathBuf>,
+        /// Read the secret from standard input.
+        #[arg(long)]
+        stdin: bool,
+        /// Format of the configuration file the secret is put into.
+        #[arg(long, value_enum, default_value_t)]
+        format: ConfigFormat,
+    },
+    /// Revolt commands for obtaining some configuration options.
+    #[command(subcommand)]
+    Revolt(RevoltSubcommands),
+}
+
+#[derive(ValueEnum, Debug, Default, Clone, Copy)]
+pub enum OutputFormat {
+    /// YAML, with sources as comments.
+    #[default]
+    Yaml,
+    /// JSON, with sources in a separate object.
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RevoltSubcommands {
+    /// Login to Revolt to obtain a new session token.
+    ///
+    /// Anything not given through the options is prompted for.
+    GetSessionToken(GetSessionTokenArguments),
+    #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+    /// Manage the sessions of the configured account.
+    #[command(subcommand)]
+    Sessions(SessionsSubcommands),
+    #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+    /// Log out of the configured session, invalidating its session token.
+    Logout {
+        #[command(flatten)]
+        config: ConfigArguments,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct GetSessionTokenArguments {
+    #[arg(long, default_value = "https://api.revolt.chat")]
+    revolt_api_url: String,
+    /// E-mail address of the account.
+    #[arg(long, env = "LURE_REVOLT_EMAIL")]
+    email: Option<String>,
+    /// Read the password of the account from a file.
+    #[arg(
+        long,
+        value_name = "PATH",
+        env = "LURE_REVOLT_PASSWORD_FILE",
+        conflicts_with = "password_stdin"
+    )]
+    password_file: Option<PathBuf>,
+    /// Read the password of the account from standard input.
+    #[arg(long)]
+    password_stdin: bool,
+    /// 2FA authentication code, used if the account has 2FA enabled.
+    #[arg(long, env = "LURE_REVOLT_TOTP_CODE", conflicts_with = "recovery_code")]
+    totp_code: Option<String>,
+    /// 2FA recovery code, used if the account has 2FA enabled.
+    #[arg(long, env = "LURE_REVOLT_RECOVERY_CODE")]
+    recovery_code: Option<String>,
+    /// Print the session token and API URL as JSON.
+    #[arg(long)]
+    json: bool,
+    /// Put the session token and API URL into an existing config file,
+    /// which is checked before logging in.
+    #[arg(long, value_name = "PATH")]
+    write_config: Option<PathBuf>,
+    /// Write the session token to a file only readable by you.
+    ///
+    /// If `--write-config` is also given, the config file refers to
+    /// this file instead of containing the session token.
+    #[arg(long, value_name = "PATH")]
+    write_secret_file: Option<PathBuf>,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+#[derive(Subcommand, Debug)]
+pub enum SessionsSubcommands {
+    /// List the sessions of the configured account.
+    List {
+        #[command(flatten)]
+        config: ConfigArguments,
+    },
+    /// Revoke a session of the configured account.
+    Revoke {
+        /// ID of the session to revoke.
+        id: String,
+        #[command(flatten)]
+        config: ConfigArguments,
+    },
+}
+
+impl Command for CommandSubcommands {
+    async fn run(&self) -> anyhow::Result<()> {
+        trace!("`config` subcommand");
+
+        match self {
+            Self::Generate { format } => {
+                trace!("`config generate` subcommand");
+                match format {
+                    ConfigFormat::Yaml => {}
+                    ConfigFormat::Toml => eprintln!(
+                        "Only YAML keeps the comments explaining the options, and options \
+                         without a default are left out of TOML. Generate the YAML file to \
+                         see all of them."
+                    ),
+                    ConfigFormat::Json => eprintln!(
+                        "Only YAML keeps the comments explaining the options. Generate the \
+                         YAML file to read them."
+                    ),
+                }
+                print!(
+                    "{}",
+                    format.convert_yaml(include_str!("../../../resources/config.sample.yaml"))?
+                );
+            }
+            #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+            Self::Init { config } => {
+                trace!("`config init` subcommand");
+
+                init::run(config.as_deref()).await?;
+            }
+            #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+            Self::Check { config, online } => {
+                use config::check::{Checker, Severity};
+
+                trace!("`config check` subcommand");
+
+                let mut checker = Checker::new(config.config.as_deref(), config.config_format);
+                let loaded_config = checker.check();
+
+                if *online {
+                    match loaded_config {
+                        Some(loaded_config) if checker.count(Severity::Error) == 0 => {
+                            checker.check_online(&loaded_config).await;
+                        }
+                        _ => {
+                            eprintln!("Skipping online checks, since the configuration has errors.")
+                        }
+                    }
+                }
+
+                for problem in checker.problems() {
+                    println!("{problem}");
+                }
+
+                let errors = checker.count(Severity::Error);
+                let warnings = checker.count(Severity::Warning);
+                if errors > 0 {
+                    anyhow::bail!(
+                        "Found {errors} error(s) and {warnings} warning(s) in the configuration."
+                    );
+                }
+
+                println!("Configuration is valid with {warnings} warning(s).");
+            }
+            #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+            Self::Show { config, format } => {
+                trace!("`config show` subcommand");
+
+                let effective_config = config::show::EffectiveConfig::load(
+                    config.config.as_deref(),
+                    config.config_format,
+                )?;
+
+                match format {
+                    OutputFormat::Yaml => print!(
+                        "{}",
+                        yaml::to_string(&effective_config.config, &|key| {
+                            effective_config
+                                .sources
+                                .get(key)
+                                .and_then(|source| source.as_str())
+                                .map(String::from)
+                        })
+                    ),
+                    OutputFormat::Json => println!(
+                        "{}",
+                        serde_json::to_string_pretty(&serde_json::json!({
+                            "config": effective_config.config,
+                            "sources": effective_config.sources,
+                        }))?
+                    ),
+                }
+            }
+            #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+            Self::PreviewTemplate(arguments) => {
+                trace!("`config preview-template` subcommand");
+
+                preview::run(arguments).await?;
+            }
+            #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+            Self::Schema => {
+                trace!("`config schema` subcommand");
+
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&config::schema::generate())?
+                );
+            }
+            #[cfg(all(
+                feature = "secrets-age",
+                any(feature = "services-lastfm", feature = "services-listenbrainz")
+            ))]
+            Self::EncryptSecret {
+                recipient,
+                identity,
+                stdin,
+                format,
+            } => {
+                trace!("`config encrypt-secret` subcommand");
+
+                let secret = if *stdin {
+                    std::io::read_to_string(std::io::stdin())?
+                        .trim_end_matches(['\r', '\n'])
+                        .to_string()
+                } else {
+                    Password::new("Secret:")
+                        .with_validator(ValueRequiredValidator::default())
+                        .without_confirmation()
+                        .prompt()?
+                };
+
+                let encrypted =
+                    config::encrypted::encrypt(&secret, recipient, identity.as_deref())?;
+                match format {
+                    ConfigFormat::Yaml => {
+                        println!("!{} |", config::encrypted::AGE_TAG);
+                        for line in encrypted.lines() {
+                            println!("  {line}");
+                        }
+                    }
+                    ConfigFormat::Toml => println!("\"\"\"\n{}\n\"\"\"", encrypted.trim_end()),
+                    ConfigFormat::Json => println!("{}", serde_json::to_string(&encrypted)?),
+                }
+            }
+            Self::Revolt(revolt_subcommand) => match revolt_subcommand {
+                RevoltSubcommands::GetSessionToken(arguments) => {
+                    trace!("`config revolt get-session-token` subcommand");
+
+                    // Problems with the config file are found before logging
+                    // in, so the new session isn't lost.
+                    let config_document = read_config_to_update(arguments)?;
+                    let Some(session_token) = login(arguments).await? else {
+                        return Ok(());
+                    };
+
+                    if let Err(error) =
+                        save_session_token(&session_token, arguments, config_document)
+                    {
+                        eprintln!("Could not save the session token, so it is printed instead.");
+                        println!("{session_token}");
+
+                        return Err(error);
+                    }
+                }
+                #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+                RevoltSubcommands::Sessions(sessions_subcommand) => match sessions_subcommand {
+                    SessionsSubcommands::List { config } => {
+                        trace!("`config revolt sessions list` subcommand");
+
+                        let revolt_client = session_revolt_client(config)?;
+                        let sessions = revolt_client.get_sessions().await?;
+
+                        println!("{:<26}  NAME", "ID");
+                        for session in sessions {
+                            println!("{:<26}  {}", session.id, session.name);
+                        }
+                    }
+                    SessionsSubcommands::Revoke { id, config } => {
+                        trace!("`config revolt sessions revoke` subcommand");
+
+                        let revolt_client = session_revolt_client(config)?;
+                        revolt_client.revoke_session(id).await?;
+
+                        println!("Session `{id}` is revoked.");
+                    }
+                },
+                #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+                RevoltSubcommands::Logout { config } => {
+                    trace!("`config revolt logout` subcommand");
+
+                    let revolt_client = session_revolt_client(config)?;
+                    revolt_client.logout().await?;
+
+                    println!("Logged out. The configured session token is no longer valid.");
+                }
+            },
+        }
+
+        Ok(())
+    }
+}
+
+/// Logs in to Revolt and returns the session token. Anything missing from
+/// the arguments is prompted for, and `None` is returned if a prompt is
+/// cancelled.
+#[expect(clippy::too_many_lines, reason = "No need to split things here yet.")]
+async fn login(arguments: &GetSessionTokenArguments) -> anyhow::Result<Option<String>> {
+    let revolt_api_url = &arguments.revolt_api_url;
+    let reqwest_client = reqwest::Client::new();
+
+    let email = match &arguments.email {
+        Some(email) => email.clone(),
+        None => {
+            let Some(email) = prompt_answer(
+                Text::new("E-mail:")
+                    .with_placeholder("i@love.cat")
+                    .with_validator(INQUIRE_EMAIL_VALIDATOR.as_ref())
+                    .prompt(),
+                "`--email` or `LURE_REVOLT_EMAIL`",
+            )?
+            else {
+                return Ok(None);
+            };
+
+            email
+        }
+    };
+    let password = if let Some(password_file) = &arguments.password_file {
+        let password = std::fs::read_to_string(password_file).map_err(|error| {
+            anyhow::anyhow!(
+                "Could not read the password file `{}`: {error}",
+                password_file.display()
+            )
+        })?;
+
+        password.trim_end_matches(['\r', '\n']).to_string()
+    } else if arguments.password_stdin {
+        let password = std::io::read_to_string(std::io::stdin())?;
+
+        password.trim_end_matches(['\r', '\n']).to_string()
+    } else {
+        let Some(password) = prompt_answer(
+            Password::new("Password:")
+                .with_validator(ValueRequiredValidator::default())
+                .with_help_message(
+                    "We won't keep your password and only use it to get a session token.",
+                )
+                .without_confirmation()
+                .prompt(),
+            "`--password-file`, `LURE_REVOLT_PASSWORD_FILE` or `--password-stdin`",
+        )?
+        else {
+            return Ok(None);
+        };
+
+        password
+    };
+
+    let login_response: LoginResponse = reqwest_client
+        .post(format!("{revolt_api_url}/auth/session/login"))
+        .json(&LoginData::Email {
+            email,
+            password: password.clone(),
+            friendly_name: Some(REVOLT_SESSION_FRIENDLY_NAME.to_string()),
+        })
+        .send()
+        .await?
+        .handle_user_friendly_error()
+        .await?
+        .json()
+        .await?;
+
+    match login_response {
+        LoginResponse::Success(session_token) => Ok(Some(session_token.token)),
+        LoginResponse::MFA {
+            ticket: mfa_ticket,
+            allowed_methods,
+        } => {
+            // Codes given through the options can't be entered again.
+            let can_retry = arguments.totp_code.is_none() && arguments.recovery_code.is_none();
+
+            loop {
+                let Some(mfa_data) = mfa_response(arguments, &allowed_methods, &password)? else {
+                    return Ok(None);
+                };
+
+                let mfa_response = reqwest_client
+                    .post(format!("{revolt_api_url}/auth/session/login"))
+                    .json(&LoginData::MFA {
+                        mfa_ticket: mfa_ticket.clone(),
+                        mfa_response: Some(mfa_data),
+                        friendly_name: Some(REVOLT_SESSION_FRIENDLY_NAME.to_string()),
+                    })
+                    .send()
+                    .await?
+                    .handle_user_friendly_error()
+                    .await;
+
+                match mfa_response {
+                    Ok(response) => match response.json::<LoginResponse>().await? {
+                        LoginResponse::Success(session_token) => {
+                            return Ok(Some(session_token.token))
+                        }
+                        LoginResponse::MFA {
+                            ticket: _,
+                            allowed_methods: _,
+                        } => unreachable!("MFA after MFA is not supposed to be possible."),
+                        LoginResponse::Disabled { user_id: _ } => {
+                            anyhow::bail!("The account is disabled.");
+                        }
+                    },
+                    Err(error)
+                        if can_retry
+                            && matches!(
+                                error.downcast_ref(),
+                                Some(CommonRevoltLoginErrors::InvalidToken)
+                            ) =>
+                    {
+                        eprintln!("{error} Please try again.");
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+        }
+        LoginResponse::Disabled { user_id: _ } => {
+            anyhow::bail!("The account is disabled.");
+        }
+    }
+}
+
+/// Picks an MFA method from `allowed_methods` and returns its response.
+/// Codes given in the arguments are used without prompting, and `None` is
+/// returned if a prompt is cancelled.
+fn mfa_response(
+    arguments: &GetSessionTokenArguments,
+    allowed_methods: &[MFAMethod],
+    password: &str,
+) -> anyhow::Result<Option<MFAData>> {
+    let ensure_allowed = |method: MFAMethod| {
+        if allowed_methods.contains(&method) {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "The account does not accept {} for 2FA.",
+                MFAMethodChoice(method)
+            ))
+        }
+    };
+
+    if let Some(totp_code) = &arguments.totp_code {
+        ensure_allowed(MFAMethod::Totp)?;
+        return Ok(Some(MFAData::Totp {
+            totp_code: totp_code.clone(),
+        }));
+    }
+    if let Some(recovery_code) = &arguments.recovery_code {
+        ensure_allowed(MFAMethod::Recovery)?;
+        return Ok(Some(MFAData::Recovery {
+            recovery_code: recovery_code.clone(),
+        }));
+    }
+
+    let method = match allowed_methods {
+        [] => anyhow::bail!("The account requires 2FA, but allows no 2FA methods."),
+        [method] => method.clone(),
+        methods => {
+            let choices = methods.iter().cloned().map(MFAMethodChoice).collect();
+            let Some(MFAMethodChoice(method)) =
+                prompt_answer(Select::new("2FA method:", choices).prompt(), "`--totp-code` (`LURE_REVOLT_TOTP_CODE`) or `--recovery-code` (`LURE_REVOLT_RECOVERY_CODE`)")?
+            else {
+                return Ok(None);
+            };
+
+            method
+        }
+    };
+
+    let mfa_data = match method {
+        MFAMethod::Password => MFAData::Password {
+            password: password.to_string(),
+        },
+        MFAMethod::Totp => {
+            let Some(totp_code) = prompt_answer(
+                Text::new("Enter 2FA authentication code:")
+                    .with_validator(INQUIRE_TOTP_VALIDATOR.as_ref())
+                    .prompt(),
+                "`--totp-code` or `LURE_REVOLT_TOTP_CODE`",
+            )?
+            else {
+                return Ok(None);
+            };
+
+            MFAData::Totp { totp_code }
+        }
+        MFAMethod::Recovery => {
+            let Some(recovery_code) = prompt_answer(
+                Text::new("Enter 2FA recovery code:")
+                    .with_validator(INQUIRE_RECOVERY_VALIDATOR.as_ref())
+                    .prompt(),
+                "`--recovery-code` or `LURE_REVOLT_RECOVERY_CODE`",
+            )?
+            else {
+                return Ok(None);
+            };
+
+            MFAData::Recovery { recovery_code }
+        }
+    };
+
+    Ok(Some(mfa_data))
+}
+
+/// Returns the answer of a prompt, or `None` if the user cancelled it. If
+/// there is no terminal to prompt on, such as in scripts, fails with a
+/// message naming the `options` that give the answer instead.
+fn prompt_answer<T>(answer: Result<T, InquireError>, options: &str) -> anyhow::Result<Option<T>> {
+    match answer {
+        Ok(answer) => Ok(Some(answer)),
+        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => Ok(None),
+        Err(InquireError::NotTTY) => {
+            anyhow::bail!("Could not prompt, since there is no terminal. Give {options} instead.")
+        }
+        Err(error) => anyhow::bail!("Could not prompt: {error}. Give {options} instead."),
+    }
+}
+
+/// [`MFAMethod`] with a user friendly name, for prompts.
+struct MFAMethodChoice(MFAMethod);
+
+impl std::fmt::Display for MFAMethodChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self.0 {
+            MFAMethod::Password => "password",
+            MFAMethod::Recovery => "recovery codes",
+            MFAMethod::Totp => "authenticator app codes",
+        })
+    }
+}
+
+/// Reads the `--write-config` file and sets the API URL in it, so
+/// `save_session_token` only has to add the token.
+fn read_config_to_update(arguments: &GetSessionTokenArguments) -> anyhow::Result<Option<String>> {
+    let Some(config_path) = arguments.write_config.as_deref() else {
+        return Ok(None);
+    };
+
+    let document = std::fs::read_to_string(config_path).map_err(|error| {
+        anyhow::anyhow!(
+            "Could not read the config file `{}`: {error}",
+            config_path.display()
+        )
+    })?;
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(config_path)
+        .map_err(|error| {
+            anyhow::anyhow!(
+                "Could not write the config file `{}`: {error}",
+                config_path.display()
+            )
+        })?;
+
+    ConfigFormat::from_path(config_path)
+        .set_value(
+            &document,
+            &["revolt", "api_url"],
+            Some(arguments.revolt_api_url.as_str()),
+        )
+        .map(Some)
+        .map_err(|error| {
+            anyhow::anyhow!(
+                "Could not update the config file `{}`: {error}",
+                config_path.display()
+            )
+        })
+}
+
+fn save_session_token(
+    session_token: &str,
+    arguments: &GetSessionTokenArguments,
+    config_document: Option<String>,
+) -> anyhow::Result<()> {
+    let revolt_api_url = arguments.revolt_api_url.as_str();
+    let config_path = arguments.write_config.as_deref();
+    let secret_file_path = arguments.write_secret_file.as_deref();
+
+    if arguments.json {
+        println!(
+            "{}",
+            serde_json::json!({
+                "session_token": session_token,
+                "api_url": revolt_api_url,
+            })
+        );
+    } else if config_path.is_none() && secret_file_path.is_none() {
+        println!(
+            "{}",
+            SUCCESSFUL_LOGIN_RESPONSE_TEMPLATE.replace("{SESSION_TOKEN}", session_token)
+        );
+
+        return Ok(());
+    }
+
+    let secret_file_path = secret_file_path
+        .map(|path| {
+            write_secret_file(path, session_token)?;
+            eprintln!("Session token is written to `{}`.", path.display());
+
+            std::path::absolute(path)
+        })
+        .transpose()?;
+
+    if let (Some(config_path), Some(document)) = (config_path, config_document) {
+        let format = ConfigFormat::from_path(config_path);
+        let document = match &secret_file_path {
+            Some(secret_file_path) => {
+                let document = format.set_value(&document, &["revolt", "session_token"], None)?;
+                format.set_value(
+                    &document,
+                    &["revolt", "session_token_file"],
+                    Some(&secret_file_path.to_string_lossy()),
+                )?
+            }
+            None => {
+                format.set_value(&document, &["revolt", "session_token"], Some(session_token))?
+            }
+        };
+
+        std::fs::write(config_path, document)?;
+        eprintln!("Config file `{}` is updated.", config_path.display());
+        if secret_file_path.is_none() {
+            warn_if_readable_by_others(config_path);
+        }
+    }
+
+    if !arguments.json {
+        eprintln!("\n{SESSION_TOKEN_WARNING}");
+    }
+
+    Ok(())
+}
+
+/// Warns if the file at `path`, which contains a secret, can be read by
+/// other users.
+fn warn_if_readable_by_others(path: &Path) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        if std::fs::metadata(path).is_ok_and(|metadata| metadata.permissions().mode() & 0o077 != 0)
+        {
+            eprintln!(
+                "Warning: `{0}` contains the session token, but can be read by other users. Restrict it with `chmod 600 {0}`, or use `--write-secret-file`.",
+                path.display()
+            );
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+fn write_secret_file(path: &Path, secret: &str) -> std::io::Result<()> {
+    use std::io::Write as _;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt as _, PermissionsExt as _};
+
+        options.mode(0o600);
+        let mut file = options.open(path)?;
+        // `mode` only applies to newly created files.
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        file.write_all(secret.as_bytes())?;
+    }
+
+    #[cfg(not(unix))]
+    options.open(path)?.write_all(secret.as_bytes())?;
+
+    Ok(())
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+fn session_revolt_client(config: &ConfigArguments) -> anyhow::Result<revolt::HttpClient> {
+    let revolt_options: config::RevoltOptions = config.figment().extract_inner("revolt")?;
+
+    let authentication = revolt::authentication(&revolt_options)?;
+    if !matches!(authentication, Authentication::SessionToken(_)) {
+        anyhow::bail!("Managing sessions requires a session token, but a bot token is configured.");
+    }
+
+    revolt::HttpClient::try_new(revolt_options.api_url, &authentication)
+}
+
+// Taken from
+// https://github.com/authifier/authifier/blob/7615a17e7b62e65fdd1294ad100f7ed3e1503b9f/crates/authifier/src/result.rs
+#[derive(thiserror::Error, Debug)]
+pub enum CommonRevoltLoginErrors {
+    #[error("The account you are trying to log in to is unverified.")]
+    UnverifiedAccount,
+    #[error("Invalid login credentials provided.")]
+    InvalidCredentials,
+    #[error("Incorrect 2FA code provided.")]
+    InvalidToken,
+
+    #[error(
+        "The entered password is compromised. Please ensure you have entered the correct password."
+    )]
+    CompromisedPassword,
+    #[error(
+        "The entered password is too short. Please ensure you have entered the correct password."
+    )]
+    ShortPassword,
+    #[error("The entered email is blacklisted. Please ensure you have entered the correct email.")]
+    Blacklisted,
+    #[error("This account is locked out. Please try again some time later.")]
+    LockedOut,
+}
+
+impl<'de> Deserialize<'de> for CommonRevoltLoginErrors {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize, Debug)]
+        struct Helper {
+            #[serde(rename = "type")]
+            error_type: String,
+        }
+
+        let helper = Helper::deserialize(deserializer)?;
+        match helper.error_type.as_str() {
+            "UnverifiedAccount" => Ok(Self::UnverifiedAccount),
+            "InvalidCredentials" => Ok(Self::InvalidCredentials),
+            "InvalidToken" => Ok(Self::InvalidToken),
+            "CompromisedPassword" => Ok(Self::CompromisedPassword),
+            "ShortPassword" => Ok(Self::ShortPassword),
+            "Blacklisted" => Ok(Self::Blacklisted),
+            "LockedOut" => Ok(Self::LockedOut),
+            _ => Err(de::Error::unknown_variant(
+                &helper.error_type,
+                &[
+                    "UnverifiedAccount",
+                    "InvalidCredentials",
+                    "InvalidToken",
+                    "CompromisedPassword",
+                    "ShortPassword",
+                    "Blacklisted",
+                    "LockedOut",
+                ],
+            )),
+        }
+    }
+}
+
+trait ReqwestResponseExt: Sized {
+    async fn handle_user_friendly_error(self) -> anyhow::Result<Self>;
+}
+
+impl ReqwestResponseExt for reqwest::Response {
+    async fn handle_user_friendly_error(self) -> anyhow::Result<Self> {
+        match self.status() {
+            StatusCode::OK | StatusCode::NO_CONTENT => Ok(self),
+            StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Err(self.json::<CommonRevoltLoginErrors>().await?.into())
+            }
+            _ => anyhow::bail!("Unexpected error: {}", self.text().await?),
+        }
+    }
+}
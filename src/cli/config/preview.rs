@@ -0,0 +1,100 @@
+#![cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+
+use clap::Args;
+
+use crate::{
+    cli::ConfigArguments,
+    config::Config,
+    revolt::status::{self, MAX_STATUS_LENGTH},
+    services::{Service, TrackInfo},
+};
+
+#[derive(Args, Debug)]
+pub struct PreviewTemplateArguments {
+    #[command(flatten)]
+    config: ConfigArguments,
+    /// Template to render instead of the configured one.
+    #[arg(long)]
+    template: Option<String>,
+    /// Artist of the track to render the template with. Without it, the
+    /// track currently playing on the enabled service is used.
+    #[arg(long, requires = "name")]
+    artist: Option<String>,
+    /// Name of the track to render the template with.
+    #[arg(long, requires = "artist")]
+    name: Option<String>,
+    /// Album of the track to render the template with.
+    #[arg(long, requires = "artist")]
+    album: Option<String>,
+}
+
+pub async fn run(arguments: &PreviewTemplateArguments) -> anyhow::Result<()> {
+    let config = arguments.config.load()?;
+    let mut status_options = config.revolt.status.clone();
+    if let Some(template) = &arguments.template {
+        status_options.template.clone_from(template);
+    }
+
+    let track = match (&arguments.artist, &arguments.name) {
+        (Some(artist), Some(name)) => Some(TrackInfo {
+            artist: artist.clone(),
+            name: name.clone(),
+            album: arguments.album.clone(),
+            cover_art_url: None,
+        }),
+        _ => live_track(&config).await?,
+    };
+
+    let max_length = status_options.max_length.min(MAX_STATUS_LENGTH);
+    println!("Template: {}", status_options.template);
+    match &track {
+        Some(track) => {
+            let filled = status::fill(&status_options.template, track);
+            let rendered = status::render(&status_options, track);
+            let filled_length = filled.chars().count();
+
+            println!("Track: {} by {}", track.name, track.artist);
+            if let Some(album) = &track.album {
+                println!("Album: {album}");
+            }
+            println!("Status: {rendered}");
+            println!(
+                "Length: {} of {max_length} characters",
+                rendered.chars().count()
+            );
+            if rendered == filled {
+                println!("Truncation: none");
+            } else {
+                println!(
+                    "Truncation: shortened from {filled_length} characters, the full status would be:"
+                );
+                println!("  {filled}");
+            }
+        }
+        None => println!("Track: nothing is playing"),
+    }
+
+    println!();
+    match &status_options.idle {
+        Some(idle) => {
+            let idle_length = idle.chars().count();
+            println!("Idle status: {idle}");
+            println!("Length: {idle_length} of {MAX_STATUS_LENGTH} characters");
+        }
+        None => println!("Idle status: none, the status is cleared when nothing is playing"),
+    }
+
+    Ok(())
+}
+
+async fn live_track(config: &Config) -> anyhow::Result<Option<TrackInfo>> {
+    let Some(enabled_service) = &config.enable else {
+        anyhow::bail!(
+            "No service is enabled to get the current track from. Enable one by setting `enable` in the configuration, or give a track with `--artist` and `--name`."
+        );
+    };
+
+    Service::try_new(config, enabled_service)?
+        .get_current_playing_track()
+        .await
+}
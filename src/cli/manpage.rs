@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use clap::{Args, CommandFactory};
+use tracing::trace;
+
+use super::{Cli, Command};
+
+#[derive(Args, Debug)]
+pub struct CommandArguments {
+    /// Write the manual pages of lure and all of its subcommands, such as
+    /// `lure-config.1`, to this directory instead of printing the one of
+    /// lure.
+    #[arg(long, value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+}
+
+impl Command for CommandArguments {
+    async fn run(&self) -> anyhow::Result<()> {
+        trace!("`manpage` subcommand");
+
+        match &self.out_dir {
+            Some(out_dir) => {
+                std::fs::create_dir_all(out_dir)?;
+                clap_mangen::generate_to(Cli::command(), out_dir)?;
+            }
+            None => clap_mangen::Man::new(Cli::command()).render(&mut std::io::stdout())?,
+        }
+
+        Ok(())
+    }
+}
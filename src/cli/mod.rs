@@ -1,6 +1,19 @@
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+use std::path::PathBuf;
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+use clap::Args;
 use clap::{Parser, Subcommand};
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+use figment::Figment;
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+use crate::{config::Config, utils::format::ConfigFormat};
 
+pub mod completions;
 pub mod config;
+pub mod manpage;
+pub mod now;
 pub mod start;
 
 pub trait Command {
@@ -19,7 +32,45 @@ pub enum Subcommands {
     #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
     /// Start lure.
     Start(start::CommandArguments),
+    #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+    /// Print the current track and the status lure would set, then exit.
+    ///
+    /// Revolt is not touched.
+    Now(now::CommandArguments),
     /// Lure configuration options
     #[command(subcommand)]
     Config(config::CommandSubcommands),
+    /// Print the shell completions of lure.
+    ///
+    /// For example, run `lure completions bash >/etc/bash_completion.d/lure`.
+    Completions(completions::CommandArguments),
+    /// Print the manual page of lure in roff format.
+    Manpage(manpage::CommandArguments),
+}
+
+/// Options for finding the config file.
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+#[derive(Args, Debug, Clone)]
+pub struct ConfigArguments {
+    /// Path of lure config file.
+    ///
+    /// If not given, a `config.yaml` (or `.yml`, `.toml`, `.json`) file is
+    /// searched for in `$XDG_CONFIG_HOME/lure`, `/etc/lure` and the current
+    /// directory.
+    #[arg(short, long, env = "LURE_CONFIG")]
+    pub config: Option<PathBuf>,
+    /// Format of the config file. Detected from its extension by default.
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub config_format: Option<ConfigFormat>,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+impl ConfigArguments {
+    pub fn figment(&self) -> Figment {
+        Config::figment(self.config.as_deref(), self.config_format)
+    }
+
+    pub fn load(&self) -> anyhow::Result<Config> {
+        Config::load(self.config.as_deref(), self.config_format)
+    }
 }
@@ -0,0 +1,72 @@
+#![cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+
+use clap::Args;
+use tracing::trace;
+
+use crate::{revolt, services::Service};
+
+use super::{Command, ConfigArguments};
+
+#[derive(Args, Debug)]
+pub struct CommandArguments {
+    #[command(flatten)]
+    config: ConfigArguments,
+    /// Print the service, track and status as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+impl Command for CommandArguments {
+    async fn run(&self) -> anyhow::Result<()> {
+        trace!("`now` subcommand");
+
+        let config = self.config.load()?;
+        let Some(enabled_service) = &config.enable else {
+            anyhow::bail!(
+                "No service is enabled. Enable one by setting `enable` in the configuration."
+            );
+        };
+
+        let track = Service::try_new(&config, enabled_service)?
+            .get_current_playing_track()
+            .await?;
+        let revolt_status = &config.revolt.status;
+        let status = track.as_ref().map_or_else(
+            || revolt_status.idle.clone(),
+            |track| Some(revolt::status::render(revolt_status, track)),
+        );
+
+        if self.json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "service": enabled_service,
+                    "track": track,
+                    "status": status,
+                }))?
+            );
+
+            return Ok(());
+        }
+
+        match &track {
+            Some(track) => {
+                println!("Track: {} by {}", track.name, track.artist);
+                if let Some(album) = &track.album {
+                    println!("Album: {album}");
+                }
+                if let Some(cover_art_url) = &track.cover_art_url {
+                    println!("Cover art: {cover_art_url}");
+                }
+            }
+            None => println!("Nothing is playing."),
+        }
+        match status {
+            Some(status) if track.is_some() => println!("Status: {status}"),
+            Some(status) => println!("Status: {status} (idle)"),
+            None => println!("Status: cleared, since no idle status is set"),
+        }
+
+        Ok(())
+    }
+}
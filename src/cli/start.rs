@@ -3,129 +3,59 @@
 use std::path::{Path, PathBuf};
 
 use clap::Args;
-use figment::{
-    providers::{Env, Format, Yaml},
-    Figment,
-};
-use figment_file_provider_adapter::FileAdapter;
-use rive_models::authentication::Authentication;
-use tokio::{signal, sync::mpsc};
+use rive_models::user::UserStatus;
+use tokio::{signal, sync::mpsc, task::JoinHandle, time};
 use tracing::{debug, trace};
 
 use crate::{
-    config::{self, RevoltStatusOptions},
-    services::ServiceProvider,
+    config::{self, Config, RevoltOptions},
+    services::Service,
 };
 use crate::{revolt, services::TrackInfo};
 
-use super::Command;
+use super::{Command, ConfigArguments};
+
+/// How long to wait for more changes after the config file changed with
+/// `--watch-config`, since editors often save in several steps.
+const CONFIG_WATCH_DEBOUNCE: time::Duration = time::Duration::from_millis(200);
 
 #[derive(Args, Debug)]
 pub struct CommandArguments {
-    /// Path of lure config file.
-    #[arg(short, long)]
-    config: Option<PathBuf>,
+    #[command(flatten)]
+    config: ConfigArguments,
+    /// Reload the config file when it or its drop-in files change, using
+    /// inotify on Linux. It is always reloaded on SIGHUP.
+    #[arg(long)]
+    watch_config: bool,
+    /// Log the changes lure would make on Revolt instead of making them.
+    ///
+    /// Revolt is still read, such as to check the token, and its rate
+    /// limits are simulated.
+    #[arg(long)]
+    dry_run: bool,
 }
 
 impl Command for CommandArguments {
     async fn run(&self) -> anyhow::Result<()> {
         trace!("`start` subcommand");
 
-        let config_path = self
-            .config
-            .as_ref()
-            .map_or_else(|| Path::new("config.yaml"), PathBuf::as_path);
-
-        let config: config::Config = Figment::new()
-            .merge(Yaml::file(config_path))
-            .merge(Env::prefixed("LURE_").split("__"))
-            .merge(FileAdapter::wrap(Yaml::file(config_path)).only(&["session_token", "api_key"]))
-            .merge(
-                FileAdapter::wrap(Env::prefixed("LURE_").split("__"))
-                    .only(&["session_token", "api_key"]),
-            )
-            .extract()?;
+        let config = self.config.load()?;
 
         let (tx, rx) = mpsc::channel::<ChannelData>(1);
 
         exit_handler(tx.clone());
+        reload_handler(tx.clone(), self.config.clone(), self.watch_config);
 
-        match config.enable {
-            Some(enabled_service) => match enabled_service {
-                // TODO: Create a macro for this.
-                #[cfg(feature = "services-lastfm")]
-                config::Services::LastFM => {
-                    #[cfg(all(feature = "services-lastfm", feature = "services-listenbrainz"))]
-                    {
-                        if config.services.lastfm.is_none() {
-                            anyhow::bail!("Last.fm is enabled, but no configuration is provided.")
-                        }
-                    }
-
-                    let mut service = crate::services::lastfm::LastFM {
-                        #[cfg(all(
-                            feature = "services-lastfm",
-                            not(feature = "services-listenbrainz")
-                        ))]
-                        options: config.services.lastfm,
-                        #[cfg(all(
-                            feature = "services-lastfm",
-                            feature = "services-listenbrainz"
-                        ))]
-                        options: config.services.lastfm.unwrap(),
-                        ..Default::default()
-                    };
-
-                    let revolt_client = revolt::HttpClient::try_new(
-                        config.revolt.api_url,
-                        &Authentication::SessionToken(config.revolt.session_token),
-                    )?;
-                    revolt_client.ping().await?;
-
-                    service.initialise()?;
-                    service.track_check_loop(tx);
-
-                    channel_listener(rx, revolt_client, config.revolt.status).await?;
-                }
-                #[cfg(feature = "services-listenbrainz")]
-                config::Services::ListenBrainz => {
-                    #[cfg(all(feature = "services-lastfm", feature = "services-listenbrainz"))]
-                    if config.services.listenbrainz.is_none() {
-                        anyhow::bail!("ListenBrainz is enabled, but no configuration is provided.")
-                    }
-
-                    let mut service = crate::services::listenbrainz::ListenBrainz {
-                        #[cfg(all(
-                            feature = "services-listenbrainz",
-                            not(feature = "services-lastfm")
-                        ))]
-                        options: config.services.listenbrainz,
-                        #[cfg(all(
-                            feature = "services-listenbrainz",
-                            feature = "services-lastfm"
-                        ))]
-                        options: config.services.listenbrainz.unwrap(),
-                        ..Default::default()
-                    };
-
-                    let revolt_client = revolt::HttpClient::try_new(
-                        config.revolt.api_url,
-                        &Authentication::SessionToken(config.revolt.session_token),
-                    )?;
-                    revolt_client.ping().await?;
-
-                    service.initialise()?;
-                    service.track_check_loop(tx);
-
-                    channel_listener(rx, revolt_client, config.revolt.status).await?;
-                }
-            },
-            None => anyhow::bail!(
-                "No service is enabled. Please enable a service in the configuration file."
-            ),
+        let revolt_client =
+            revolt::HttpClient::try_from_options(&config.revolt)?.with_dry_run(self.dry_run);
+        revolt_client.ping().await?;
+        if self.dry_run {
+            tracing::info!("dry run: changes to Revolt are only logged");
         }
 
-        Ok(())
+        let service_task = spawn_service(&config, tx.clone())?;
+
+        channel_listener(rx, tx, revolt_client, config, service_task).await
     }
 }
 
@@ -133,115 +63,437 @@ impl Command for CommandArguments {
 #[derive(Debug)]
 pub enum ChannelData {
     Track(Option<TrackInfo>),
+    Reload(Box<Config>),
     Exit(bool),
 }
 
+/// Starts checking the enabled service for the current track.
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+fn spawn_service(
+    config: &Config,
+    tx: mpsc::Sender<ChannelData>,
+) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
+    let Some(enabled_service) = &config.enable else {
+        anyhow::bail!("No service is enabled. Please enable a service in the configuration file.")
+    };
+
+    Ok(Service::try_new(config, enabled_service)?.track_check_loop(tx))
+}
+
 #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
 async fn channel_listener(
     mut rx: mpsc::Receiver<ChannelData>,
+    tx: mpsc::Sender<ChannelData>,
     revolt_client: revolt::HttpClient,
-    revolt_status: RevoltStatusOptions,
+    config: Config,
+    service_task: JoinHandle<anyhow::Result<()>>,
 ) -> anyhow::Result<()> {
-    use tokio::time;
-
     trace!("looping `channel_listener`");
 
-    let first_status = revolt_client.get_status().await?;
-    let mut previous_track: Option<TrackInfo> = None;
+    let mut listener = Listener::try_new(tx, revolt_client, config, service_task).await?;
 
     while let Some(data) = rx.recv().await {
         match data {
-            ChannelData::Track(track) if track.is_some() => {
-                if previous_track == track {
-                    debug!(
-                        "current track `{track:?}` is the same as previous track `{previous_track:?}`, skipping status update"
-                    );
-                    continue;
+            ChannelData::Track(track) if track.is_some() => listener.show_track(track).await?,
+            ChannelData::Track(_) => listener.show_idle().await?,
+            ChannelData::Reload(config) => listener.reload(*config).await,
+            ChannelData::Exit(graceful) => {
+                tracing::info!("stopping lure");
+
+                if graceful {
+                    listener.restore().await?;
                 }
 
-                let status = track.as_ref().map_or_else(
-                    || revolt_status.idle.clone(),
-                    |track| {
-                        Some(
-                            revolt_status
-                                .template
-                                .replace("%ARTIST%", &track.artist)
-                                .replace("%NAME%", &track.name),
-                        )
-                    },
-                );
+                break;
+            }
+        }
+    }
 
-                match revolt_client.set_status(status).await {
-                    Ok(()) => {
-                        previous_track = track;
-                    }
-                    Err(error) => {
-                        if let revolt::RevoltAPIError::RateLimitExceeded(remaining) = error {
-                            tracing::warn!("rate limit exceeded, waiting until the time limit is over to update status...");
-                            time::sleep(time::Duration::from_millis(remaining.try_into()?)).await;
-                        } else {
-                            tracing::error!("error occurred while updating status: {:?}", error);
-                            return Err(error.into());
-                        }
+    trace!("got out of `channel_listener` loop");
+
+    Ok(())
+}
+
+/// The state of `channel_listener`.
+struct Listener {
+    tx: mpsc::Sender<ChannelData>,
+    config: Config,
+    service_task: JoinHandle<anyhow::Result<()>>,
+    revolt_client: revolt::HttpClient,
+    first_status: UserStatus,
+    artwork: Option<revolt::artwork::Artwork>,
+    channel_sink: Option<revolt::channel::ChannelSink>,
+    profile_section: Option<revolt::profile::ProfileSection>,
+    previous_track: Option<TrackInfo>,
+    /// Whether to update the status even if the track did not change, such
+    /// as after the config is reloaded.
+    refresh: bool,
+}
+
+impl Listener {
+    async fn try_new(
+        tx: mpsc::Sender<ChannelData>,
+        revolt_client: revolt::HttpClient,
+        config: Config,
+        service_task: JoinHandle<anyhow::Result<()>>,
+    ) -> anyhow::Result<Self> {
+        let first_status = revolt_client.get_status().await?;
+        let artwork = new_artwork(&revolt_client, &config.revolt).await?;
+        let channel_sink = new_channel_sink(&revolt_client, &config.revolt).await?;
+        let profile_section = new_profile_section(&revolt_client, &config.revolt).await?;
+
+        Ok(Self {
+            tx,
+            config,
+            service_task,
+            revolt_client,
+            first_status,
+            artwork,
+            channel_sink,
+            profile_section,
+            previous_track: None,
+            refresh: false,
+        })
+    }
+
+    async fn show_track(&mut self, track: Option<TrackInfo>) -> anyhow::Result<()> {
+        let track_changed = self.previous_track != track;
+        if !track_changed && !self.refresh {
+            debug!(
+                "current track `{track:?}` is the same as previous track `{:?}`, skipping status update",
+                self.previous_track
+            );
+            return Ok(());
+        }
+
+        let revolt_status = &self.config.revolt.status;
+        let status = track.as_ref().map_or_else(
+            || revolt_status.idle.clone(),
+            |track| Some(revolt::status::render(revolt_status, track)),
+        );
+
+        let mut edit = revolt::UserEdit::default();
+        edit.status(
+            status,
+            self.presence_update(revolt_status.presence.listening),
+        );
+        if let Some(artwork) = self.artwork.as_mut() {
+            let cover_art_url = track
+                .as_ref()
+                .and_then(|track| track.cover_art_url.as_deref());
+            if let Err(error) = artwork
+                .show(&self.revolt_client, cover_art_url, &mut edit)
+                .await
+            {
+                tracing::warn!("could not update artwork: {error}");
+            }
+        }
+        if let Some(profile_section) = self.profile_section.as_mut() {
+            profile_section.show(track.as_ref(), &mut edit);
+        }
+
+        match self.revolt_client.edit_user(&edit).await {
+            Ok(()) => {
+                if let Some(artwork) = self.artwork.as_mut() {
+                    artwork.applied();
+                }
+
+                // Reloading should not post the same track again.
+                if let (true, Some(channel_sink), Some(track)) =
+                    (track_changed, self.channel_sink.as_mut(), track.as_ref())
+                {
+                    if let Err(error) = channel_sink.post(&self.revolt_client, track).await {
+                        tracing::warn!("could not post track to channel: {error}");
                     }
                 }
+
+                self.previous_track = track;
+                self.refresh = false;
             }
-            ChannelData::Track(track) if track.is_none() => {
-                if previous_track.is_none() {
-                    debug!("no track to update, skipping status update");
-                    continue;
+            Err(error) => {
+                if let revolt::RevoltAPIError::RateLimitExceeded(remaining) = error {
+                    tracing::warn!("rate limit exceeded, waiting until the time limit is over to update status...");
+                    time::sleep(time::Duration::from_millis(remaining.try_into()?)).await;
+                } else if let revolt::RevoltAPIError::InvalidStatus(_) = error {
+                    tracing::error!("{error}, skipping status update for this track");
+                    self.previous_track = track;
+                    self.refresh = false;
+                } else {
+                    tracing::error!("error occurred while updating status: {:?}", error);
+                    return Err(error.into());
                 }
+            }
+        }
 
-                debug!("no track to update, reverting to previous status");
-                match revolt_client.set_status(first_status.clone()).await {
-                    Ok(()) => {
-                        previous_track = None;
-                    }
-                    Err(error) => match error {
-                        revolt::RevoltAPIError::RateLimitExceeded(_remaining) => {
-                            tracing::warn!("rate limit exceeded, skipping status update");
-                            continue;
-                        }
-                        _ => {
-                            return Err(error.into());
-                        }
-                    },
+        Ok(())
+    }
+
+    async fn show_idle(&mut self) -> anyhow::Result<()> {
+        if self.previous_track.is_none() && !self.refresh {
+            debug!("no track to update, skipping status update");
+            return Ok(());
+        }
+
+        debug!("no track to update, setting idle status");
+        let revolt_status = &self.config.revolt.status;
+        let mut edit = revolt::UserEdit::default();
+        edit.status(
+            revolt_status
+                .idle
+                .clone()
+                .or_else(|| self.first_status.text.clone()),
+            self.presence_update(revolt_status.presence.idle),
+        );
+        if let Some(artwork) = self.artwork.as_mut() {
+            if let Err(error) = artwork.restore(&self.revolt_client, &mut edit).await {
+                tracing::warn!("could not restore artwork: {error}");
+            }
+        }
+        if let Some(profile_section) = self.profile_section.as_mut() {
+            profile_section.show(None, &mut edit);
+        }
+
+        match self.revolt_client.edit_user(&edit).await {
+            Ok(()) => {
+                if let Some(artwork) = self.artwork.as_mut() {
+                    artwork.applied();
                 }
+
+                self.previous_track = None;
+                self.refresh = false;
             }
-            ChannelData::Track(_) => unreachable!("unexpected `ChannelData::Track` variant"),
-            ChannelData::Exit(graceful) => {
-                tracing::info!("stopping lure");
+            Err(error) => match error {
+                revolt::RevoltAPIError::RateLimitExceeded(_remaining) => {
+                    tracing::warn!("rate limit exceeded, skipping status update");
+                }
+                _ => {
+                    return Err(error.into());
+                }
+            },
+        }
 
-                if graceful {
-                    loop {
-                        match revolt_client.set_status(first_status.clone()).await {
-                            Ok(()) => break,
-                            Err(error) => match error {
-                                revolt::RevoltAPIError::RateLimitExceeded(remaining) => {
-                                    if remaining > 0 {
-                                        tracing::warn!("rate limit exceeded, waiting until the time limit is over to revert status...");
-                                        time::sleep(time::Duration::from_millis(
-                                            remaining.try_into()?,
-                                        ))
-                                        .await;
-                                    }
-                                }
-                                _ => {
-                                    return Err(error.into());
-                                }
-                            },
-                        }
-                    }
+        Ok(())
+    }
+
+    /// Applies a reloaded config, restarting only the parts whose options
+    /// changed. Parts that fail to restart keep their current options.
+    async fn reload(&mut self, mut config: Config) {
+        tracing::info!("reloading config");
+
+        if config.enable != self.config.enable || config.services != self.config.services {
+            tracing::info!("service options changed, restarting the service");
+
+            match spawn_service(&config, self.tx.clone()) {
+                Ok(service_task) => {
+                    self.service_task.abort();
+                    self.service_task = service_task;
+                }
+                Err(error) => {
+                    tracing::error!(
+                        "could not restart the service, keeping the current one: {error}"
+                    );
+                    config.enable = self.config.enable.take();
+                    config.services = std::mem::take(&mut self.config.services);
                 }
+            }
+        }
 
-                break;
+        let (old, new) = (&self.config.revolt, &config.revolt);
+        if (
+            &old.api_url,
+            &old.authentication,
+            &old.session_token,
+            &old.bot_token,
+        ) != (
+            &new.api_url,
+            &new.authentication,
+            &new.session_token,
+            &new.bot_token,
+        ) {
+            tracing::info!("Revolt options changed, reconnecting to Revolt");
+
+            if let Err(error) = self.reconnect(&config.revolt).await {
+                tracing::error!(
+                    "could not reconnect to Revolt, keeping the current connection: {error}"
+                );
+
+                let old = &self.config.revolt;
+                config.revolt.api_url.clone_from(&old.api_url);
+                config.revolt.authentication = old.authentication.clone();
+                config.revolt.session_token.clone_from(&old.session_token);
+                config.revolt.bot_token.clone_from(&old.bot_token);
+            }
+        }
+
+        let artwork_changed = config.revolt.artwork != self.config.revolt.artwork;
+        let profile_changed = config.revolt.profile != self.config.revolt.profile;
+        let mut edit = revolt::UserEdit::default();
+        if let (true, Some(artwork)) = (artwork_changed, self.artwork.as_mut()) {
+            if let Err(error) = artwork.restore(&self.revolt_client, &mut edit).await {
+                tracing::warn!("could not restore artwork: {error}");
             }
         }
+        if let (true, Some(profile_section)) = (profile_changed, self.profile_section.as_mut()) {
+            profile_section.restore(&mut edit);
+        }
+        if !edit.is_empty() {
+            if let Err(error) = self.revolt_client.edit_user(&edit).await {
+                tracing::warn!("could not restore artwork and profile content: {error}");
+            }
+        }
+
+        if artwork_changed {
+            self.artwork = new_artwork(&self.revolt_client, &config.revolt)
+                .await
+                .unwrap_or_else(|error| {
+                    tracing::warn!("could not set up artwork: {error}");
+                    None
+                });
+            self.refresh = true;
+        }
+
+        if config.revolt.channel != self.config.revolt.channel {
+            self.channel_sink = new_channel_sink(&self.revolt_client, &config.revolt)
+                .await
+                .unwrap_or_else(|error| {
+                    tracing::warn!("could not set up channel: {error}");
+                    None
+                });
+        }
+
+        if profile_changed {
+            self.profile_section = new_profile_section(&self.revolt_client, &config.revolt)
+                .await
+                .unwrap_or_else(|error| {
+                    tracing::warn!("could not set up profile content: {error}");
+                    None
+                });
+            self.refresh = true;
+        }
+
+        if config.revolt.status != self.config.revolt.status {
+            self.refresh = true;
+        }
+
+        self.config = config;
+
+        tracing::info!("reloaded config");
     }
 
-    trace!("got out of `channel_listener` loop");
+    /// Switches to a new Revolt client. If it belongs to another account,
+    /// the current account is restored first. On error, the current client
+    /// is kept untouched.
+    async fn reconnect(&mut self, revolt_options: &RevoltOptions) -> anyhow::Result<()> {
+        let revolt_client = revolt::HttpClient::try_from_options(revolt_options)?
+            .with_dry_run(self.revolt_client.is_dry_run());
+        revolt_client.ping().await?;
+
+        let account_changed =
+            revolt_client.get_user().await?.id != self.revolt_client.get_user().await?.id;
+        if account_changed {
+            // Everything that can fail is done before anything is switched.
+            let first_status = revolt_client.get_status().await?;
+            let artwork = new_artwork(&revolt_client, revolt_options).await?;
+            let channel_sink = new_channel_sink(&revolt_client, revolt_options).await?;
+            let profile_section = new_profile_section(&revolt_client, revolt_options).await?;
+            self.restore().await?;
+
+            self.first_status = first_status;
+            self.artwork = artwork;
+            self.channel_sink = channel_sink;
+            self.profile_section = profile_section;
+        }
 
-    Ok(())
+        self.revolt_client = revolt_client;
+        self.refresh = true;
+
+        Ok(())
+    }
+
+    /// Restores everything lure changed on the account.
+    async fn restore(&mut self) -> anyhow::Result<()> {
+        let mut edit = revolt::UserEdit::default();
+        edit.status(self.first_status.text.clone(), self.presence_update(None));
+        if let Some(artwork) = self.artwork.as_mut() {
+            if let Err(error) = artwork.restore(&self.revolt_client, &mut edit).await {
+                tracing::warn!("could not restore artwork: {error}");
+            }
+        }
+        if let Some(profile_section) = self.profile_section.as_mut() {
+            profile_section.restore(&mut edit);
+        }
+
+        loop {
+            match self.revolt_client.edit_user(&edit).await {
+                Ok(()) => break,
+                Err(error) => match error {
+                    revolt::RevoltAPIError::RateLimitExceeded(remaining) => {
+                        if remaining > 0 {
+                            tracing::warn!("rate limit exceeded, waiting until the time limit is over to revert status...");
+                            time::sleep(time::Duration::from_millis(remaining.try_into()?)).await;
+                        }
+                    }
+                    _ => {
+                        return Err(error.into());
+                    }
+                },
+            }
+        }
+
+        if let Some(artwork) = self.artwork.as_mut() {
+            artwork.applied();
+        }
+
+        Ok(())
+    }
+
+    fn presence_update(&self, presence: Option<config::Presence>) -> revolt::PresenceUpdate {
+        let presence_options = &self.config.revolt.status.presence;
+        if presence_options.listening.is_none() && presence_options.idle.is_none() {
+            return revolt::PresenceUpdate::Keep;
+        }
+
+        presence.map_or_else(
+            || self.first_status.presence.clone().into(),
+            |presence| revolt::PresenceUpdate::Set(presence.into()),
+        )
+    }
+}
+
+async fn new_artwork(
+    revolt_client: &revolt::HttpClient,
+    revolt_options: &RevoltOptions,
+) -> Result<Option<revolt::artwork::Artwork>, revolt::RevoltAPIError> {
+    match revolt_options.artwork {
+        Some(target) => Ok(Some(
+            revolt::artwork::Artwork::try_new(revolt_client, target).await?,
+        )),
+        None => Ok(None),
+    }
+}
+
+async fn new_channel_sink(
+    revolt_client: &revolt::HttpClient,
+    revolt_options: &RevoltOptions,
+) -> Result<Option<revolt::channel::ChannelSink>, revolt::RevoltAPIError> {
+    match &revolt_options.channel {
+        Some(options) => Ok(Some(
+            revolt::channel::ChannelSink::try_new(revolt_client, options.clone()).await?,
+        )),
+        None => Ok(None),
+    }
+}
+
+async fn new_profile_section(
+    revolt_client: &revolt::HttpClient,
+    revolt_options: &RevoltOptions,
+) -> Result<Option<revolt::profile::ProfileSection>, revolt::RevoltAPIError> {
+    match &revolt_options.profile {
+        Some(options) => Ok(Some(
+            revolt::profile::ProfileSection::try_new(revolt_client, options.clone()).await?,
+        )),
+        None => Ok(None),
+    }
 }
 
 #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
@@ -274,3 +526,162 @@ fn exit_handler(tx: mpsc::Sender<ChannelData>) {
     });
     trace!("spawned task for `exit_handler`");
 }
+
+/// Reloads the config on SIGHUP and, if `watch` is set, when the config
+/// file or its drop-in files change.
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+fn reload_handler(tx: mpsc::Sender<ChannelData>, config: ConfigArguments, watch: bool) {
+    trace!("spawning task for `reload_handler`");
+    tokio::spawn(async move {
+        let (changes_tx, mut changes_rx) = mpsc::unbounded_channel();
+        let mut watcher = if watch {
+            ConfigWatcher::try_new(&config::config_path(config.config.as_deref()), changes_tx)
+                .map_err(|error| {
+                    tracing::error!(
+                        "could not watch the config file, it is only reloaded on SIGHUP: {error}"
+                    );
+                })
+                .ok()
+        } else {
+            None
+        };
+
+        #[cfg(unix)]
+        let mut hangup = signal::unix::signal(signal::unix::SignalKind::hangup())
+            .expect("SIGHUP signal handler could not be created");
+        #[cfg(windows)]
+        let mut hangup = ();
+
+        loop {
+            tokio::select! {
+                () = hangup_received(&mut hangup) => {
+                    tracing::info!("received SIGHUP, reloading config");
+                }
+                Some(event) = changes_rx.recv() => {
+                    let Some(watcher) = watcher.as_mut() else {
+                        continue;
+                    };
+                    if !watcher.handle(&event) {
+                        continue;
+                    }
+
+                    // Wait for the rest of the save, then reload once.
+                    time::sleep(CONFIG_WATCH_DEBOUNCE).await;
+                    while let Ok(event) = changes_rx.try_recv() {
+                        watcher.handle(&event);
+                    }
+                    tracing::info!("config file changed, reloading config");
+                }
+            }
+
+            match config.load() {
+                Ok(config) => {
+                    if tx
+                        .send(ChannelData::Reload(Box::new(config)))
+                        .await
+                        .is_err()
+                    {
+                        debug!("lure is stopping, not watching the config anymore");
+                        break;
+                    }
+                }
+                Err(error) => {
+                    tracing::error!("could not reload config, keeping the current one: {error}");
+                }
+            }
+        }
+    });
+    trace!("spawned task for `reload_handler`");
+}
+
+/// Watches the config file and its drop-in directory. The directories are
+/// watched instead of the files, so that files replaced by renaming them,
+/// as many editors save, are noticed too.
+struct ConfigWatcher {
+    watcher: notify::RecommendedWatcher,
+    config_path: PathBuf,
+    drop_in_directory: PathBuf,
+    drop_in_directory_watched: bool,
+}
+
+impl ConfigWatcher {
+    fn try_new(
+        config_path: &Path,
+        changes_tx: mpsc::UnboundedSender<notify::Event>,
+    ) -> notify::Result<Self> {
+        use notify::Watcher as _;
+
+        // Event paths start with the watched path, so it has to be absolute
+        // to compare them with the config path.
+        let config_path = std::path::absolute(config_path)?;
+        let directory = config_path.parent().unwrap_or_else(|| Path::new("/"));
+        let drop_in_directory = directory.join(config::DROP_IN_DIRECTORY);
+
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+                Ok(event) => {
+                    // The receiver is only gone when lure is stopping.
+                    let _ = changes_tx.send(event);
+                }
+                Err(error) => tracing::warn!("could not watch the config file: {error}"),
+            })?;
+        watcher.watch(directory, notify::RecursiveMode::NonRecursive)?;
+
+        let mut config_watcher = Self {
+            watcher,
+            config_path,
+            drop_in_directory,
+            drop_in_directory_watched: false,
+        };
+        config_watcher.watch_drop_in_directory();
+
+        Ok(config_watcher)
+    }
+
+    /// Returns whether `event` changed the config.
+    fn handle(&mut self, event: &notify::Event) -> bool {
+        if event.kind.is_access() || event.kind.is_other() {
+            return false;
+        }
+
+        event.paths.iter().any(|path| {
+            if *path == self.drop_in_directory {
+                self.watch_drop_in_directory();
+            }
+
+            *path == self.config_path
+                || *path == self.drop_in_directory
+                || path.parent() == Some(self.drop_in_directory.as_path())
+        })
+    }
+
+    /// Watches the drop-in directory, once it exists.
+    fn watch_drop_in_directory(&mut self) {
+        use notify::Watcher as _;
+
+        if self.drop_in_directory_watched || !self.drop_in_directory.is_dir() {
+            return;
+        }
+
+        match self
+            .watcher
+            .watch(&self.drop_in_directory, notify::RecursiveMode::NonRecursive)
+        {
+            Ok(()) => self.drop_in_directory_watched = true,
+            Err(error) => tracing::warn!(
+                "could not watch `{}`: {error}",
+                self.drop_in_directory.display()
+            ),
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn hangup_received(hangup: &mut signal::unix::Signal) {
+    hangup.recv().await;
+}
+
+#[cfg(windows)]
+async fn hangup_received((): &mut ()) {
+    std::future::pending().await
+}
@@ -0,0 +1,518 @@
+//! Validation of the configuration without starting lure.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use figment::{Figment, Metadata, Profile};
+use regex::Regex;
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    revolt::{self, status},
+    services::ServiceProvider as _,
+    utils::format::ConfigFormat,
+};
+
+#[cfg(feature = "services-lastfm")]
+use super::LastFMServiceOptions;
+#[cfg(feature = "services-listenbrainz")]
+use super::ListenBrainzServiceOptions;
+use super::{
+    config_path, describe_source, file_location, Config, RevoltArtworkTarget, RevoltAuthentication,
+    RevoltChannelOptions, RevoltOptions, RevoltProfileOptions, RevoltStatusOptions, ServiceOptions,
+    Services,
+};
+
+static PLACEHOLDER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new("%[A-Z_]+%").expect("placeholder regex is somehow invalid now."));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Problem {
+    pub severity: Severity,
+    /// Where the value comes from, such as `config.yaml:12` or an
+    /// environment variable.
+    pub location: Option<String>,
+    /// The dotted path of the value.
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{location}: ")?;
+        }
+        write!(f, "{}: ", self.severity)?;
+        if !self.key.is_empty() {
+            write!(f, "`{}`: ", self.key)?;
+        }
+
+        f.write_str(&self.message)
+    }
+}
+
+/// Loads the configuration the same way `start` does and collects every
+/// problem found along the way.
+pub struct Checker {
+    path: PathBuf,
+    figment: Figment,
+    document: Option<String>,
+    problems: Vec<Problem>,
+}
+
+impl Checker {
+    pub fn new(path: Option<&Path>, format: Option<ConfigFormat>) -> Self {
+        let path = config_path(path);
+
+        Self {
+            figment: Config::figment(Some(&path), format),
+            path,
+            document: None,
+            problems: Vec::new(),
+        }
+    }
+
+    pub fn problems(&self) -> &[Problem] {
+        &self.problems
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.problems
+            .iter()
+            .filter(|problem| problem.severity == severity)
+            .count()
+    }
+
+    /// Checks the configuration and returns it if it could be loaded.
+    pub fn check(&mut self) -> Option<Config> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(document) => self.document = Some(document),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                self.push(
+                    Severity::Warning,
+                    "",
+                    format!(
+                        "config file `{}` does not exist, only environment variables are used",
+                        self.path.display()
+                    ),
+                );
+            }
+            Err(error) => {
+                self.push(
+                    Severity::Error,
+                    "",
+                    format!(
+                        "config file `{}` could not be read: {error}",
+                        self.path.display()
+                    ),
+                );
+            }
+        }
+
+        // Sections are checked one by one first, since deserialisation
+        // stops at the first error.
+        let sections_valid = [
+            self.check_section::<Option<Services>>("enable"),
+            #[cfg(feature = "services-lastfm")]
+            self.check_section::<LastFMServiceOptions>("services.lastfm"),
+            #[cfg(feature = "services-listenbrainz")]
+            self.check_section::<ListenBrainzServiceOptions>("services.listenbrainz"),
+            self.check_section::<RevoltStatusOptions>("revolt.status"),
+            self.check_section::<RevoltAuthentication>("revolt.authentication"),
+            self.check_section::<RevoltArtworkTarget>("revolt.artwork"),
+            self.check_section::<RevoltChannelOptions>("revolt.channel"),
+            self.check_section::<RevoltProfileOptions>("revolt.profile"),
+        ]
+        .into_iter()
+        .all(|valid| valid);
+
+        match self.figment.extract::<Config>() {
+            Ok(config) => {
+                self.check_enable(config.enable.as_ref(), &config.services);
+                self.check_services(&config.services);
+                self.check_revolt(&config.revolt);
+
+                Some(config)
+            }
+            Err(error) => {
+                if sections_valid {
+                    self.push_figment_error(error, "");
+                }
+
+                // The values of the parts that could be loaded are still
+                // checked, so every problem is reported at once.
+                if let Ok(services) = self.figment.extract_inner::<ServiceOptions>("services") {
+                    self.check_services(&services);
+                }
+                if let Ok(revolt) = self.figment.extract_inner::<RevoltOptions>("revolt") {
+                    self.check_revolt(&revolt);
+                }
+
+                None
+            }
+        }
+    }
+
+    /// Verifies the Revolt token and the service credentials against their
+    /// APIs.
+    pub async fn check_online(&mut self, config: &Config) {
+        match revolt::HttpClient::try_from_options(&config.revolt) {
+            Ok(revolt_client) => {
+                if let Err(error) = revolt_client.ping().await {
+                    self.push(
+                        Severity::Error,
+                        revolt_token_key(&config.revolt.authentication),
+                        format!("Revolt did not accept the token: {error}"),
+                    );
+                }
+            }
+            Err(error) => self.push(
+                Severity::Error,
+                revolt_token_key(&config.revolt.authentication),
+                error.to_string(),
+            ),
+        }
+
+        #[cfg(feature = "services-lastfm")]
+        if let Some(options) = lastfm_options(&config.services) {
+            let mut service = crate::services::lastfm::LastFM {
+                options: options.clone(),
+                ..Default::default()
+            };
+
+            if let Err(error) = service.initialise().map(|_| ()) {
+                self.push(Severity::Error, "services.lastfm", error.to_string());
+            } else if let Err(error) = service.verify().await {
+                self.push(
+                    Severity::Error,
+                    "services.lastfm",
+                    format!("Last.fm did not accept the credentials: {error}"),
+                );
+            }
+        }
+
+        #[cfg(feature = "services-listenbrainz")]
+        if let Some(options) = listenbrainz_options(&config.services) {
+            let mut service = crate::services::listenbrainz::ListenBrainz {
+                options: options.clone(),
+                ..Default::default()
+            };
+
+            if let Err(error) = service.initialise().map(|_| ()) {
+                self.push(Severity::Error, "services.listenbrainz", error.to_string());
+            } else if let Err(error) = service.verify().await {
+                self.push(
+                    Severity::Error,
+                    "services.listenbrainz",
+                    format!("ListenBrainz did not accept the credentials: {error}"),
+                );
+            }
+        }
+    }
+
+    fn check_section<T: DeserializeOwned>(&mut self, section: &str) -> bool {
+        if self.figment.find_value(section).is_err() {
+            return true;
+        }
+
+        match self.figment.extract_inner::<T>(section) {
+            Ok(_) => true,
+            Err(error) => {
+                self.push_figment_error(error, section);
+                false
+            }
+        }
+    }
+
+    #[cfg_attr(
+        not(all(feature = "services-lastfm", feature = "services-listenbrainz")),
+        allow(unused_variables)
+    )]
+    fn check_enable(&mut self, enable: Option<&Services>, services: &ServiceOptions) {
+        match enable {
+            None => self.push(Severity::Error, "enable", "no service is enabled".into()),
+            #[cfg(all(feature = "services-lastfm", feature = "services-listenbrainz"))]
+            Some(Services::LastFM) if services.lastfm.is_none() => self.push(
+                Severity::Error,
+                "services.lastfm",
+                "Last.fm is enabled, but no configuration is provided".into(),
+            ),
+            #[cfg(all(feature = "services-lastfm", feature = "services-listenbrainz"))]
+            Some(Services::ListenBrainz) if services.listenbrainz.is_none() => self.push(
+                Severity::Error,
+                "services.listenbrainz",
+                "ListenBrainz is enabled, but no configuration is provided".into(),
+            ),
+            Some(_) => {}
+        }
+    }
+
+    fn check_services(&mut self, services: &ServiceOptions) {
+        #[cfg(feature = "services-lastfm")]
+        if let Some(options) = lastfm_options(services) {
+            self.check_not_empty("services.lastfm.username", &options.username);
+            self.check_not_empty("services.lastfm.api_key", &options.api_key);
+        }
+
+        #[cfg(feature = "services-listenbrainz")]
+        if let Some(options) = listenbrainz_options(services) {
+            self.check_not_empty("services.listenbrainz.username", &options.username);
+            self.check_url("services.listenbrainz.api_url", &options.api_url);
+        }
+    }
+
+    fn check_revolt(&mut self, revolt: &RevoltOptions) {
+        self.check_url("revolt.api_url", &revolt.api_url);
+        if let Err(error) = revolt::authentication(revolt) {
+            self.push(
+                Severity::Error,
+                revolt_token_key(&revolt.authentication),
+                error.to_string(),
+            );
+        }
+
+        let status = &revolt.status;
+        self.check_template("revolt.status.template", &status.template);
+        for invalid in status.invalid_values() {
+            self.push(invalid.severity, invalid.key, invalid.message);
+        }
+
+        if let Some(profile) = &revolt.profile {
+            self.check_template("revolt.profile.template", &profile.template);
+            self.check_template(
+                "revolt.profile.recent_track_template",
+                &profile.recent_track_template,
+            );
+            self.check_not_empty("revolt.profile.start_marker", &profile.start_marker);
+            self.check_not_empty("revolt.profile.end_marker", &profile.end_marker);
+            if profile.start_marker == profile.end_marker {
+                self.push(
+                    Severity::Error,
+                    "revolt.profile.end_marker",
+                    "must be different from `start_marker`".into(),
+                );
+            }
+        }
+
+        if let Some(channel) = &revolt.channel {
+            self.check_not_empty("revolt.channel.id", &channel.id);
+        }
+    }
+
+    fn check_not_empty(&mut self, key: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.push(Severity::Error, key, "must not be empty".into());
+        }
+    }
+
+    fn check_url(&mut self, key: &str, url: &str) {
+        match Url::parse(url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            Ok(url) => self.push(
+                Severity::Error,
+                key,
+                format!("`{}` is not an HTTP URL", url.scheme()),
+            ),
+            Err(error) => self.push(Severity::Error, key, format!("invalid URL: {error}")),
+        }
+    }
+
+    fn check_template(&mut self, key: &str, template: &str) {
+        let mut has_placeholder = false;
+
+        for placeholder in PLACEHOLDER_REGEX.find_iter(template) {
+            if status::PLACEHOLDERS.contains(&placeholder.as_str()) {
+                has_placeholder = true;
+            } else {
+                self.push(
+                    Severity::Warning,
+                    key,
+                    format!(
+                        "unknown placeholder `{}`, known placeholders are {}",
+                        placeholder.as_str(),
+                        status::PLACEHOLDERS.join(", ")
+                    ),
+                );
+            }
+        }
+
+        if !has_placeholder {
+            self.push(
+                Severity::Warning,
+                key,
+                "contains no placeholders, so it never changes".into(),
+            );
+        }
+    }
+
+    fn push_figment_error(&mut self, error: figment::Error, section: &str) {
+        let section = section
+            .split('.')
+            .filter(|key| !key.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        for error in error {
+            // `extract_inner` appends the section to the path instead of
+            // prepending it.
+            let mut path = error.path.clone();
+            if !section.is_empty() && path.ends_with(&section) {
+                path.truncate(path.len() - section.len());
+                path.splice(0..0, section.iter().cloned());
+            }
+
+            let location = self.locate(
+                error.metadata.as_ref(),
+                error.profile.as_ref().unwrap_or(&Profile::Default),
+                &path,
+            );
+
+            self.problems.push(Problem {
+                severity: Severity::Error,
+                location,
+                key: path.join("."),
+                message: error.kind.to_string(),
+            });
+        }
+    }
+
+    fn push(&mut self, severity: Severity, key: &str, message: String) {
+        let path = key
+            .split('.')
+            .filter(|key| !key.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let location = self.locate(
+            self.figment.find_metadata(key),
+            self.figment.profile(),
+            &path,
+        );
+
+        self.problems.push(Problem {
+            severity,
+            location,
+            key: key.to_string(),
+            message,
+        });
+    }
+
+    fn locate(
+        &self,
+        metadata: Option<&Metadata>,
+        profile: &Profile,
+        path: &[String],
+    ) -> Option<String> {
+        match metadata {
+            Some(metadata) => Some(describe_source(metadata, profile, path)),
+            None if self.document.is_some() => Some(file_location(&self.path, path)),
+            None => None,
+        }
+    }
+}
+
+/// The key holding the Revolt token for the authentication type.
+const fn revolt_token_key(authentication: &RevoltAuthentication) -> &'static str {
+    match authentication {
+        RevoltAuthentication::SessionToken => "revolt.session_token",
+        RevoltAuthentication::BotToken => "revolt.bot_token",
+        RevoltAuthentication::Credential { .. } => "revolt.authentication",
+    }
+}
+
+#[cfg(feature = "services-lastfm")]
+const fn lastfm_options(services: &ServiceOptions) -> Option<&LastFMServiceOptions> {
+    #[cfg(not(feature = "services-listenbrainz"))]
+    return Some(&services.lastfm);
+    #[cfg(feature = "services-listenbrainz")]
+    return services.lastfm.as_ref();
+}
+
+#[cfg(feature = "services-listenbrainz")]
+const fn listenbrainz_options(services: &ServiceOptions) -> Option<&ListenBrainzServiceOptions> {
+    #[cfg(not(feature = "services-lastfm"))]
+    return Some(&services.listenbrainz);
+    #[cfg(feature = "services-lastfm")]
+    return services.listenbrainz.as_ref();
+}
+
+#[cfg(all(test, feature = "services-listenbrainz"))]
+mod tests {
+    use super::*;
+
+    /// Checks `document` as a config file, and returns the problems with the
+    /// path of the file.
+    fn check(name: &str, document: &str) -> (PathBuf, Option<Config>, Vec<String>) {
+        let directory =
+            std::env::temp_dir().join(format!("lure-check-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("config.yaml");
+        std::fs::write(&path, document).unwrap();
+
+        let mut checker = Checker::new(Some(&path), None);
+        let config = checker.check();
+        let problems = checker.problems().iter().map(ToString::to_string).collect();
+
+        std::fs::remove_dir_all(directory).unwrap();
+        (path, config, problems)
+    }
+
+    #[test]
+    fn reports_value_problems_with_load_errors() {
+        let (path, config, problems) = check(
+            "load-error",
+            "enable: spotify\nservices:\n  listenbrainz:\n    username: cat\nrevolt:\n  session_token: token\n  status:\n    template: Listening\n    max_length: 500\n",
+        );
+        let path = path.display();
+
+        assert!(config.is_none());
+        assert_eq!(problems.len(), 3, "{problems:#?}");
+        assert!(problems[0].starts_with(&format!("{path}:1: error: `enable`: ")));
+        assert_eq!(
+            problems[1..],
+            [
+                format!("{path}:8: warning: `revolt.status.template`: contains no placeholders, so it never changes"),
+                format!("{path}:9: warning: `revolt.status.max_length`: is more than 128, but Revolt does not accept longer statuses, so 128 is used instead"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_values_at_their_line() {
+        let (path, config, problems) = check(
+            "invalid-value",
+            &format!(
+                "enable: listenbrainz\nservices:\n  listenbrainz:\n    username: \" \"\nrevolt:\n  session_token: token\n  status:\n    idle: {}\n",
+                "x".repeat(129)
+            ),
+        );
+        let path = path.display();
+
+        assert!(config.is_some());
+        assert_eq!(
+            problems,
+            [
+                format!("{path}:4: error: `services.listenbrainz.username`: must not be empty"),
+                format!("{path}:8: error: `revolt.status.idle`: is 129 characters long, but Revolt does not accept statuses longer than 128 characters"),
+            ]
+        );
+    }
+}
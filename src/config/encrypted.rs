@@ -0,0 +1,273 @@
+//! Secrets encrypted with [age](https://age-encryption.org), given as
+//! `!age "-----BEGIN AGE ENCRYPTED FILE-----…"` in YAML, or as the armored
+//! text alone in other formats and environment variables.
+
+use std::path::{Path, PathBuf};
+
+use figment::{
+    value::{Dict, Map, Value},
+    Error, Metadata, Profile, Provider,
+};
+
+use super::SECRET_KEYS;
+
+/// The YAML tag of encrypted secrets.
+pub const AGE_TAG: &str = "age";
+
+/// The first line of an armored age file.
+pub const AGE_ARMOR_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+
+/// Decrypts the encrypted secrets of `provider` with the age identity file
+/// at `identity`. Only the decrypted secrets are provided.
+pub struct AgeAdapter<P> {
+    pub provider: P,
+    pub identity: Option<PathBuf>,
+}
+
+impl<P: Provider> Provider for AgeAdapter<P> {
+    fn metadata(&self) -> Metadata {
+        self.provider.metadata()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut data = Map::new();
+        for (profile, dict) in self.provider.data()? {
+            let mut path = Vec::new();
+            let secrets = self.decrypt_secrets(&dict, &mut path).map_err(|message| {
+                let mut error = Error::from(message);
+                error.path = path;
+                error
+            })?;
+
+            data.insert(profile, secrets);
+        }
+
+        Ok(data)
+    }
+}
+
+impl<P> AgeAdapter<P> {
+    /// On error, `path` is left at the key that failed.
+    fn decrypt_secrets(&self, dict: &Dict, path: &mut Vec<String>) -> Result<Dict, String> {
+        let mut secrets = Dict::new();
+
+        for (key, value) in dict {
+            path.push(key.clone());
+
+            let encrypted = SECRET_KEYS
+                .contains(&key.as_str())
+                .then(|| encrypted_secret(value))
+                .flatten();
+            match (value, encrypted) {
+                (_, Some(encrypted)) => {
+                    let identity = self.identity.as_deref().ok_or_else(|| {
+                        String::from("is encrypted, but no age identity file is given with `age_identity` or `LURE_AGE_IDENTITY`")
+                    })?;
+                    secrets.insert(key.clone(), decrypt(encrypted, identity)?.into());
+                }
+                (Value::Dict(_, child), None) => {
+                    let child_secrets = self.decrypt_secrets(child, path)?;
+                    if !child_secrets.is_empty() {
+                        secrets.insert(key.clone(), child_secrets.into());
+                    }
+                }
+                _ => {}
+            }
+
+            path.pop();
+        }
+
+        Ok(secrets)
+    }
+}
+
+/// Returns the armored text of an encrypted secret. A YAML tag is read by
+/// figment as a dictionary with the tag as its only key.
+fn encrypted_secret(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(_, value) if value.trim_start().starts_with(AGE_ARMOR_HEADER) => Some(value),
+        Value::Dict(_, dict) if dict.len() == 1 => dict.get(AGE_TAG)?.as_str(),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "secrets-age")]
+fn decrypt(encrypted: &str, identity: &Path) -> Result<String, String> {
+    use std::io::Read;
+
+    use age::{armor::ArmoredReader, Decryptor, IdentityFile};
+
+    let identities = IdentityFile::from_file(identity.to_string_lossy().into_owned())
+        .map_err(|error| error.to_string())
+        .and_then(|identity_file| {
+            identity_file
+                .into_identities()
+                .map_err(|error| error.to_string())
+        })
+        .map_err(|error| {
+            format!(
+                "could not read age identity file `{}`: {error}",
+                identity.display()
+            )
+        })?;
+
+    let mut secret = String::new();
+    Decryptor::new(ArmoredReader::new(encrypted.trim().as_bytes()))
+        .and_then(|decryptor| {
+            decryptor.decrypt(identities.iter().map(|identity| identity.as_ref() as _))
+        })
+        .map_err(|error| format!("could not decrypt: {error}"))?
+        .read_to_string(&mut secret)
+        .map_err(|error| format!("could not decrypt: {error}"))?;
+
+    Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[cfg(not(feature = "secrets-age"))]
+fn decrypt(_: &str, _: &Path) -> Result<String, String> {
+    Err(String::from(
+        "is encrypted, but lure is built without age support, rebuild it with the `secrets-age` feature",
+    ))
+}
+
+/// Encrypts `secret` to the age `recipients` and to the recipients of the
+/// `identity` file, and returns the armored text.
+#[cfg(feature = "secrets-age")]
+pub fn encrypt(
+    secret: &str,
+    recipients: &[String],
+    identity: Option<&Path>,
+) -> anyhow::Result<String> {
+    use std::io::Write;
+
+    use age::{
+        armor::{ArmoredWriter, Format},
+        Encryptor, IdentityFile, Recipient,
+    };
+
+    let mut all_recipients = recipients
+        .iter()
+        .map(|recipient| {
+            recipient
+                .parse::<age::x25519::Recipient>()
+                .map(|recipient| Box::new(recipient) as Box<dyn Recipient + Send>)
+                .map_err(|error| {
+                    anyhow::anyhow!("`{recipient}` is not a valid age recipient: {error}")
+                })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if let Some(identity) = identity {
+        let identity_file = IdentityFile::from_file(identity.to_string_lossy().into_owned())
+            .map_err(|error| {
+                anyhow::anyhow!(
+                    "Could not read the age identity file `{}`: {error}",
+                    identity.display()
+                )
+            })?;
+        all_recipients.extend(identity_file.to_recipients()?);
+    }
+    if all_recipients.is_empty() {
+        anyhow::bail!(
+            "Give a recipient with `--recipient`, or an identity file with `--identity`."
+        );
+    }
+
+    let encryptor = Encryptor::with_recipients(
+        all_recipients
+            .iter()
+            .map(|recipient| recipient.as_ref() as &dyn Recipient),
+    )?;
+    let mut encrypted = Vec::new();
+    let mut writer = encryptor.wrap_output(ArmoredWriter::wrap_output(
+        &mut encrypted,
+        Format::AsciiArmor,
+    )?)?;
+    writer.write_all(secret.as_bytes())?;
+    writer.finish()?.finish()?;
+
+    Ok(String::from_utf8(encrypted)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use figment::{
+        providers::{Format, Yaml},
+        Figment,
+    };
+
+    use super::*;
+
+    #[test]
+    fn encrypted_secret_reads_tags_and_armored_strings() {
+        let armored = format!("{AGE_ARMOR_HEADER}\nYWdl\n-----END AGE ENCRYPTED FILE-----\n");
+        let figment = Figment::from(Yaml::string(&format!(
+            "tagged: !age |\n  {}\nindented: \"  {AGE_ARMOR_HEADER}\"\nplain: token\n",
+            armored.trim_end().replace('\n', "\n  ")
+        )));
+
+        let tagged = figment.find_value("tagged").unwrap();
+        assert_eq!(encrypted_secret(&tagged), Some(armored.as_str()));
+        let indented = figment.find_value("indented").unwrap();
+        assert!(encrypted_secret(&indented).is_some());
+        assert_eq!(
+            encrypted_secret(&figment.find_value("plain").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn age_adapter_requires_an_identity() {
+        let figment = Figment::from(AgeAdapter {
+            provider: Yaml::string(&format!(
+                "revolt:\n  api_url: \"{AGE_ARMOR_HEADER}\"\n  session_token: !age \"{AGE_ARMOR_HEADER}\"\n"
+            )),
+            identity: None,
+        });
+        let error = figment
+            .extract_inner::<String>("revolt.session_token")
+            .unwrap_err();
+
+        assert_eq!(error.path, ["revolt", "session_token"]);
+        assert!(error.to_string().contains("no age identity file"));
+    }
+
+    #[cfg(feature = "secrets-age")]
+    #[test]
+    fn encrypted_secrets_are_decrypted() {
+        let directory = std::env::temp_dir().join(format!("lure-age-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let identity = directory.join("identity.txt");
+        std::fs::write(
+            &identity,
+            "AGE-SECRET-KEY-1GQ9778VQXMMJVE8SK7J6VT8UJ4HDQAJUVSFCWCM02D8GEWQ72PVQ2Y5J33\n",
+        )
+        .unwrap();
+
+        let encrypted = encrypt("token\n", &[], Some(&identity)).unwrap();
+        assert!(encrypted.starts_with(AGE_ARMOR_HEADER));
+        // The recipient of the identity above.
+        let recipients = [String::from(
+            "age1t7rxyev2z3rw82stdlrrepyc39nvn86l5078zqkf5uasdy86jp6svpy7pa",
+        )];
+        let encrypted_to_recipient = encrypt("key", &recipients, None).unwrap();
+
+        let figment = Figment::from(AgeAdapter {
+            provider: Yaml::string(&format!(
+                "revolt:\n  session_token: !age |\n    {}\nservices:\n  lastfm:\n    api_key: |\n      {}\n",
+                encrypted.trim_end().replace('\n', "\n    "),
+                encrypted_to_recipient.trim_end().replace('\n', "\n      "),
+            )),
+            identity: Some(identity),
+        });
+        assert_eq!(
+            figment.extract_inner::<String>("revolt.session_token"),
+            Ok(String::from("token"))
+        );
+        assert_eq!(
+            figment.extract_inner::<String>("services.lastfm.api_key"),
+            Ok(String::from("key"))
+        );
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,258 @@
+//! Check intervals, given as seconds or duration strings like `15s`, `2m`
+//! or `500ms`. Fractional seconds such as `16.5` are rejected, since
+//! `16s 500ms` says the same without rounding.
+
+use std::{fmt, time::Duration};
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{
+        InstanceType, NumberValidation, Schema, SchemaObject, StringValidation, SubschemaValidation,
+    },
+    JsonSchema,
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The check interval used when none is given.
+const DEFAULT: Duration = Duration::from_secs(16);
+
+/// The longest check interval, since longer ones leave the status stale.
+pub const MAXIMUM: Duration = Duration::from_secs(60 * 60);
+
+/// How often a service is checked for the current track. Intervals shorter
+/// than `MINIMUM_MILLISECONDS` are rejected to stay in the rate limits of
+/// the service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckInterval<const MINIMUM_MILLISECONDS: u64>(Duration);
+
+impl<const MINIMUM_MILLISECONDS: u64> CheckInterval<MINIMUM_MILLISECONDS> {
+    pub const MINIMUM: Duration = Duration::from_millis(MINIMUM_MILLISECONDS);
+
+    pub fn try_new(duration: Duration) -> Result<Self, String> {
+        if duration < Self::MINIMUM {
+            Err(format!(
+                "check interval `{}` is too short, it must be at least `{}` to stay in the rate limits of the service",
+                humantime::format_duration(duration),
+                humantime::format_duration(Self::MINIMUM)
+            ))
+        } else if duration > MAXIMUM {
+            Err(format!(
+                "check interval `{}` is too long, it must be at most `{}`",
+                humantime::format_duration(duration),
+                humantime::format_duration(MAXIMUM)
+            ))
+        } else {
+            Ok(Self(duration))
+        }
+    }
+
+    pub const fn as_duration(self) -> Duration {
+        self.0
+    }
+}
+
+impl<const MINIMUM_MILLISECONDS: u64> Default for CheckInterval<MINIMUM_MILLISECONDS> {
+    fn default() -> Self {
+        Self(DEFAULT)
+    }
+}
+
+impl<const MINIMUM_MILLISECONDS: u64> fmt::Display for CheckInterval<MINIMUM_MILLISECONDS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        humantime::format_duration(self.0).fmt(f)
+    }
+}
+
+impl<const MINIMUM_MILLISECONDS: u64> Serialize for CheckInterval<MINIMUM_MILLISECONDS> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, const MINIMUM_MILLISECONDS: u64> Deserialize<'de>
+    for CheckInterval<MINIMUM_MILLISECONDS>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor<const MINIMUM_MILLISECONDS: u64>;
+
+        impl<const MINIMUM_MILLISECONDS: u64> de::Visitor<'_> for Visitor<MINIMUM_MILLISECONDS> {
+            type Value = CheckInterval<MINIMUM_MILLISECONDS>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("seconds or a duration such as `15s`, `2m` or `500ms`")
+            }
+
+            fn visit_u64<E: de::Error>(self, seconds: u64) -> Result<Self::Value, E> {
+                CheckInterval::try_new(Duration::from_secs(seconds)).map_err(E::custom)
+            }
+
+            fn visit_i64<E: de::Error>(self, seconds: i64) -> Result<Self::Value, E> {
+                let seconds = u64::try_from(seconds)
+                    .map_err(|_| E::custom("check interval must not be negative"))?;
+
+                self.visit_u64(seconds)
+            }
+
+            fn visit_f64<E: de::Error>(self, seconds: f64) -> Result<Self::Value, E> {
+                Err(E::custom(format!(
+                    "check interval `{seconds}` has fractional seconds, use a duration like `16s 500ms` instead"
+                )))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                if let Ok(seconds) = value.trim().parse::<u64>() {
+                    return self.visit_u64(seconds);
+                }
+                if let Ok(seconds) = value.trim().parse::<f64>() {
+                    return self.visit_f64(seconds);
+                }
+
+                let duration = humantime::parse_duration(value).map_err(|error| {
+                    E::custom(format!(
+                        "`{value}` is not a valid duration ({error}), use something like `15s`, `2m` or `500ms`"
+                    ))
+                })?;
+
+                CheckInterval::try_new(duration).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+impl<const MINIMUM_MILLISECONDS: u64> JsonSchema for CheckInterval<MINIMUM_MILLISECONDS> {
+    fn schema_name() -> String {
+        String::from("CheckInterval")
+    }
+
+    // The minimum differs between services, so the schema can't be shared.
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let seconds = SchemaObject {
+            instance_type: Some(InstanceType::Integer.into()),
+            number: Some(Box::new(NumberValidation {
+                minimum: Some(Self::MINIMUM.as_secs_f64().ceil()),
+                maximum: Some(MAXIMUM.as_secs_f64()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let duration = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(String::from(r"^\s*([0-9]+\s*[a-zA-Z]+\s*)+$")),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        Schema::Object(SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![seconds.into(), duration.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::{value::Error, IntoDeserializer};
+
+    use super::*;
+
+    type LastFMInterval = CheckInterval<5000>;
+    type ListenBrainzInterval = CheckInterval<1000>;
+
+    fn parse<T: Deserialize<'static>>(
+        value: impl IntoDeserializer<'static, Error>,
+    ) -> Result<T, String> {
+        T::deserialize(value.into_deserializer()).map_err(|error| error.to_string())
+    }
+
+    #[test]
+    fn integers_are_seconds() {
+        assert_eq!(
+            parse::<LastFMInterval>(15_u64).unwrap().as_duration(),
+            Duration::from_secs(15)
+        );
+        assert_eq!(
+            parse::<LastFMInterval>(15_i64).unwrap().as_duration(),
+            Duration::from_secs(15)
+        );
+        assert_eq!(
+            parse::<LastFMInterval>("15").unwrap().as_duration(),
+            Duration::from_secs(15)
+        );
+        assert!(parse::<LastFMInterval>(-15_i64)
+            .unwrap_err()
+            .contains("must not be negative"));
+    }
+
+    #[test]
+    fn strings_are_durations() {
+        assert_eq!(
+            parse::<LastFMInterval>("15s").unwrap().as_duration(),
+            Duration::from_secs(15)
+        );
+        assert_eq!(
+            parse::<LastFMInterval>("2m").unwrap().as_duration(),
+            Duration::from_secs(120)
+        );
+        assert_eq!(
+            parse::<ListenBrainzInterval>("1500ms")
+                .unwrap()
+                .as_duration(),
+            Duration::from_millis(1500)
+        );
+    }
+
+    #[test]
+    fn intervals_below_the_minimum_are_rejected() {
+        assert!(parse::<LastFMInterval>("4999ms")
+            .unwrap_err()
+            .contains("must be at least `5s`"));
+        assert!(parse::<LastFMInterval>(5_u64).is_ok());
+        assert!(parse::<ListenBrainzInterval>("999ms")
+            .unwrap_err()
+            .contains("must be at least `1s`"));
+        assert!(parse::<ListenBrainzInterval>(1_u64).is_ok());
+    }
+
+    #[test]
+    fn intervals_above_the_maximum_are_rejected() {
+        assert!(parse::<ListenBrainzInterval>("1h").is_ok());
+        assert!(parse::<ListenBrainzInterval>("1h 1s")
+            .unwrap_err()
+            .contains("must be at most `1h`"));
+        assert!(parse::<LastFMInterval>(3601_u64)
+            .unwrap_err()
+            .contains("must be at most `1h`"));
+    }
+
+    #[test]
+    fn unparsable_intervals_are_rejected() {
+        assert!(parse::<LastFMInterval>("soon")
+            .unwrap_err()
+            .contains("`soon` is not a valid duration"));
+        assert!(parse::<LastFMInterval>(16.5_f64)
+            .unwrap_err()
+            .contains("has fractional seconds"));
+        assert!(parse::<LastFMInterval>("16.5")
+            .unwrap_err()
+            .contains("has fractional seconds"));
+    }
+
+    #[test]
+    fn intervals_display_as_durations() {
+        let interval = parse::<LastFMInterval>(90_u64).unwrap();
+
+        assert_eq!(interval.to_string(), "1m 30s");
+        assert_eq!(parse::<LastFMInterval>("1m 30s").unwrap(), interval);
+    }
+}
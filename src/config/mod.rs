@@ -0,0 +1,749 @@
+#![cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+
+use std::path::{Path, PathBuf};
+
+use check::Severity;
+use encrypted::AgeAdapter;
+use figment::{
+    providers::{Env, Format, Json, Toml, Yaml},
+    value::magic::RelativePathBuf,
+    Figment, Metadata, Profile, Source,
+};
+use figment_file_provider_adapter::FileAdapter;
+use schemars::JsonSchema;
+use secret::SecretAdapter;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    revolt::status::MAX_STATUS_LENGTH,
+    utils::{format::ConfigFormat, yaml},
+};
+
+pub mod check;
+pub mod encrypted;
+pub mod interval;
+pub mod schema;
+pub mod secret;
+pub mod show;
+
+/// The config file used when no path is given and none is found.
+pub const DEFAULT_CONFIG_PATH: &str = "config.yaml";
+
+/// The directory next to the config file whose files are merged over it.
+pub const DROP_IN_DIRECTORY: &str = "config.d";
+
+/// The names of the config file searched for in the config directories.
+const CONFIG_FILE_NAMES: &[&str] = &["config.yaml", "config.yml", "config.toml", "config.json"];
+
+/// The prefix of the environment variables read into the config.
+pub const ENV_PREFIX: &str = "LURE_";
+/// The separator of nested keys in environment variable names.
+pub const ENV_SEPARATOR: &str = "__";
+
+/// Keys whose values can be read from elsewhere with a
+/// [`secret::SecretSource`] suffix, such as `_file`.
+const SECRET_KEYS: &[&str] = &["session_token", "bot_token", "api_key"];
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+/// The configuration of lure.
+#[derive(Deserialize, Serialize, JsonSchema, Debug)]
+pub struct Config {
+    /// Which service to enable for checking your listening status.
+    pub enable: Option<Services>,
+    /// Configuration for the services.
+    pub services: ServiceOptions,
+    /// Configuration for Revolt.
+    pub revolt: RevoltOptions,
+    /// The age identity file that decrypts encrypted secrets, relative to
+    /// the config file.
+    pub age_identity: Option<PathBuf>,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+impl Config {
+    /// Builds the figment that merges the config file, its drop-in files
+    /// and environment variables. The format of the config file is detected
+    /// from its extension if not given.
+    pub fn figment(path: Option<&Path>, format: Option<ConfigFormat>) -> Figment {
+        let path = config_path(path);
+        let format = format.unwrap_or_else(|| ConfigFormat::from_path(&path));
+        let files = std::iter::once((path.clone(), format))
+            .chain(drop_in_paths(&path).into_iter().map(|drop_in_path| {
+                let format = ConfigFormat::from_path(&drop_in_path);
+                (drop_in_path, format)
+            }))
+            .collect::<Vec<_>>();
+
+        let env = || Env::prefixed(ENV_PREFIX).split(ENV_SEPARATOR);
+
+        let mut figment = Figment::new();
+        for (file, format) in &files {
+            figment = Self::merge_file(figment, file, *format, MergeStage::Values);
+        }
+        figment = figment.merge(env());
+
+        // The identity that decrypts secrets is given in the same sources.
+        let age_identity = figment
+            .extract_inner::<RelativePathBuf>("age_identity")
+            .ok()
+            .map(|age_identity| age_identity.relative());
+        for (file, format) in &files {
+            figment = Self::merge_file(
+                figment,
+                file,
+                *format,
+                MergeStage::Secrets(age_identity.as_deref()),
+            );
+        }
+
+        figment
+            .merge(FileAdapter::wrap(env()).only(SECRET_KEYS))
+            .merge(SecretAdapter(env()))
+            .merge(AgeAdapter {
+                provider: env(),
+                identity: age_identity,
+            })
+    }
+
+    /// Merges the part of `file` given by `stage`.
+    fn merge_file(
+        figment: Figment,
+        file: &Path,
+        format: ConfigFormat,
+        stage: MergeStage,
+    ) -> Figment {
+        match format {
+            ConfigFormat::Yaml => Self::merge_file_with::<Yaml>(figment, file, stage),
+            ConfigFormat::Toml => Self::merge_file_with::<Toml>(figment, file, stage),
+            ConfigFormat::Json => Self::merge_file_with::<Json>(figment, file, stage),
+        }
+    }
+
+    fn merge_file_with<F: Format + 'static>(
+        figment: Figment,
+        file: &Path,
+        stage: MergeStage,
+    ) -> Figment {
+        match stage {
+            MergeStage::Values => figment.merge(F::file(file)),
+            MergeStage::Secrets(age_identity) => figment
+                .merge(FileAdapter::wrap(F::file(file)).only(SECRET_KEYS))
+                .merge(SecretAdapter(F::file(file)))
+                .merge(AgeAdapter {
+                    provider: F::file(file),
+                    identity: age_identity.map(Path::to_path_buf),
+                }),
+        }
+    }
+
+    pub fn load(path: Option<&Path>, format: Option<ConfigFormat>) -> anyhow::Result<Self> {
+        let config: Self = Self::figment(path, format).extract()?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Checks the values that deserializing can't, so lure doesn't fail
+    /// only when Revolt rejects them.
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Some(invalid) = self
+            .revolt
+            .status
+            .invalid_values()
+            .into_iter()
+            .find(|invalid| invalid.severity == Severity::Error)
+        {
+            anyhow::bail!("`{}` {}.", invalid.key, invalid.message);
+        }
+
+        Ok(())
+    }
+}
+
+/// A value that deserializing accepts, but lure can't use as it is.
+#[derive(Debug)]
+pub struct InvalidValue {
+    pub severity: Severity,
+    /// The dotted path of the value.
+    pub key: &'static str,
+    pub message: String,
+}
+
+/// What [`Config::merge_file`] merges of a config file.
+#[derive(Clone, Copy)]
+enum MergeStage<'a> {
+    /// The values as they are.
+    Values,
+    /// The secrets read from the suffixed secret keys, and the encrypted
+    /// secrets decrypted with the age identity file.
+    Secrets(Option<&'a Path>),
+}
+
+/// Returns `path`, or the first config file found in
+/// `$XDG_CONFIG_HOME/lure`, `/etc/lure` and, for configs from before those
+/// were searched, the current directory. `$LURE_CONFIG` is read into `path`
+/// by the CLI.
+pub fn config_path(path: Option<&Path>) -> PathBuf {
+    if let Some(path) = path {
+        return path.to_path_buf();
+    }
+
+    match find_config(&config_directories()) {
+        Some(path) => {
+            tracing::debug!("using config file `{}`", path.display());
+            path
+        }
+        None => {
+            tracing::debug!("no config file found, using `{DEFAULT_CONFIG_PATH}`");
+            PathBuf::from(DEFAULT_CONFIG_PATH)
+        }
+    }
+}
+
+/// Returns the first config file in `directories`.
+/// Returns where a new config file is created so that [`config_path`]
+/// finds it: the config file it already finds, or `config.yaml` in the
+/// first config directory.
+pub fn new_config_path() -> PathBuf {
+    let directories = config_directories();
+
+    find_config(&directories).unwrap_or_else(|| directories[0].join(DEFAULT_CONFIG_PATH))
+}
+
+/// The directories searched for a config file, in order.
+fn config_directories() -> Vec<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|config_home| !config_home.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+
+    config_home
+        .map(|config_home| config_home.join("lure"))
+        .into_iter()
+        .chain([PathBuf::from("/etc/lure"), PathBuf::new()])
+        .collect()
+}
+
+fn find_config(directories: &[PathBuf]) -> Option<PathBuf> {
+    directories
+        .iter()
+        .flat_map(|directory| {
+            CONFIG_FILE_NAMES
+                .iter()
+                .map(move |name| directory.join(name))
+        })
+        .find(|path| path.is_file())
+}
+
+/// Returns the files in the drop-in directory next to `config_path`, sorted
+/// by name so they are merged in a predictable order.
+pub fn drop_in_paths(config_path: &Path) -> Vec<PathBuf> {
+    let directory = config_path
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(DROP_IN_DIRECTORY);
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    let mut paths = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .is_some_and(|extension| ["yaml", "yml", "toml", "json"].contains(&extension))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    paths
+}
+
+/// Describes where a value comes from, such as `config.yaml:12` or the name
+/// of an environment variable.
+pub fn describe_source(metadata: &Metadata, profile: &Profile, path: &[String]) -> String {
+    match &metadata.source {
+        Some(Source::File(file)) => file_location(file, path),
+        _ if path.is_empty() => metadata.name.to_string(),
+        _ if metadata.name.contains(ENV_PREFIX) => {
+            format!("{ENV_PREFIX}{}", path.join(ENV_SEPARATOR).to_uppercase())
+        }
+        _ => metadata.interpolate(profile, path),
+    }
+}
+
+/// Formats `file` with the line of the key at `path`, if found. Lines are
+/// only looked up in YAML files.
+pub fn file_location(file: &Path, path: &[String]) -> String {
+    let path = path.iter().map(String::as_str).collect::<Vec<_>>();
+    // Figment stores absolute paths, which are noisy for files in the
+    // current directory.
+    let file = std::env::current_dir()
+        .ok()
+        .and_then(|current_dir| file.strip_prefix(current_dir).ok())
+        .unwrap_or(file);
+
+    let document = (ConfigFormat::from_path(file) == ConfigFormat::Yaml)
+        .then(|| std::fs::read_to_string(file).ok())
+        .flatten();
+
+    match document.and_then(|document| yaml::line_of(&document, &path)) {
+        Some(line) => format!("{}:{line}", file.display()),
+        None => file.display().to_string(),
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Services {
+    /// `Last.fm` service.
+    #[cfg(feature = "services-lastfm")]
+    LastFM,
+    /// `ListenBrainz` service.
+    #[cfg(feature = "services-listenbrainz")]
+    ListenBrainz,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, PartialEq, Eq)]
+pub struct ServiceOptions {
+    /// Options for the `Last.fm` service.
+    #[cfg(all(feature = "services-lastfm", not(feature = "services-listenbrainz")))]
+    pub lastfm: LastFMServiceOptions,
+    /// Options for the `Last.fm` service.
+    #[cfg(all(feature = "services-lastfm", feature = "services-listenbrainz"))]
+    pub lastfm: Option<LastFMServiceOptions>,
+
+    /// Options for the `ListenBrainz` service.
+    #[cfg(all(feature = "services-listenbrainz", not(feature = "services-lastfm")))]
+    pub listenbrainz: ListenBrainzServiceOptions,
+    /// Options for the `ListenBrainz` service.
+    #[cfg(all(feature = "services-listenbrainz", feature = "services-lastfm"))]
+    pub listenbrainz: Option<ListenBrainzServiceOptions>,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+#[derive(Deserialize, Serialize, JsonSchema, Debug)]
+pub struct RevoltOptions {
+    /// Status options.
+    #[serde(default = "RevoltStatusOptions::default")]
+    pub status: RevoltStatusOptions,
+    /// The API URL of the instance.
+    #[serde(default = "default_revolt_api_url")]
+    pub api_url: String,
+    /// How to authenticate with Revolt.
+    #[serde(default)]
+    pub authentication: RevoltAuthentication,
+    /// The session token of the account.
+    pub session_token: Option<String>,
+    /// The token of the bot.
+    pub bot_token: Option<String>,
+    /// Where to show the cover art of the current track.
+    pub artwork: Option<RevoltArtworkTarget>,
+    /// Channel to post the current track to.
+    pub channel: Option<RevoltChannelOptions>,
+    /// Profile bio options.
+    pub profile: Option<RevoltProfileOptions>,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+impl Default for RevoltOptions {
+    fn default() -> Self {
+        Self {
+            status: RevoltStatusOptions::default(),
+            api_url: default_revolt_api_url(),
+            authentication: RevoltAuthentication::default(),
+            session_token: None,
+            bot_token: None,
+            artwork: None,
+            channel: None,
+            profile: None,
+        }
+    }
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct RevoltProfileOptions {
+    /// The now playing template.
+    #[serde(default = "default_revolt_profile_template")]
+    pub template: String,
+    /// The text to show when not listening to anything.
+    pub idle: Option<String>,
+    /// How many recently played tracks to show.
+    #[serde(default)]
+    pub recent_tracks: usize,
+    /// The template for each recently played track.
+    #[serde(default = "default_revolt_profile_recent_track_template")]
+    pub recent_track_template: String,
+    /// The line that marks the start of the now playing section.
+    #[serde(default = "default_revolt_profile_start_marker")]
+    pub start_marker: String,
+    /// The line that marks the end of the now playing section.
+    #[serde(default = "default_revolt_profile_end_marker")]
+    pub end_marker: String,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RevoltAuthentication {
+    /// Authenticate with `session_token`.
+    #[default]
+    SessionToken,
+    /// Authenticate with `bot_token`.
+    BotToken,
+    /// Authenticate with a token read from a credentials directory.
+    Credential {
+        /// The name of the credential.
+        name: String,
+        /// The type of the token.
+        #[serde(default)]
+        token_type: RevoltTokenType,
+        /// The credentials directory. Defaults to `$CREDENTIALS_DIRECTORY`.
+        directory: Option<PathBuf>,
+    },
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RevoltTokenType {
+    /// Session token of a user account.
+    #[default]
+    SessionToken,
+    /// Token of a bot account.
+    BotToken,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct RevoltChannelOptions {
+    /// The ID of the channel.
+    pub id: String,
+    /// Whether to send a new message or edit a single pinned message.
+    #[serde(default)]
+    pub mode: RevoltChannelMode,
+    /// The ID of the message to edit.
+    pub message_id: Option<String>,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RevoltChannelMode {
+    /// Send a new message for every track.
+    #[default]
+    Send,
+    /// Edit a single message, which is pinned.
+    Edit,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RevoltArtworkTarget {
+    /// Profile background.
+    Background,
+    /// Avatar.
+    Avatar,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct RevoltStatusOptions {
+    /// The status text to set.
+    #[serde(default = "default_revolt_status_template")]
+    pub template: String,
+    /// The status emoji to set.
+    pub idle: Option<String>,
+    /// The maximum length of the status text.
+    #[serde(default = "default_revolt_status_max_length")]
+    pub max_length: usize,
+    /// The text to append to truncated fields.
+    #[serde(default = "default_revolt_status_ellipsis")]
+    pub ellipsis: String,
+    /// Presence options.
+    #[serde(default)]
+    pub presence: RevoltPresenceOptions,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+impl RevoltStatusOptions {
+    /// Returns the problems with the values that deserializing can't find.
+    /// Errors stop lure from loading the config, warnings are only shown by
+    /// `lure config check`.
+    pub fn invalid_values(&self) -> Vec<InvalidValue> {
+        let mut invalid_values = Vec::new();
+
+        if self.max_length == 0 {
+            invalid_values.push(InvalidValue {
+                severity: Severity::Error,
+                key: "revolt.status.max_length",
+                message: String::from("must be greater than 0"),
+            });
+        } else if self.max_length > MAX_STATUS_LENGTH {
+            invalid_values.push(InvalidValue {
+                severity: Severity::Warning,
+                key: "revolt.status.max_length",
+                message: format!(
+                    "is more than {MAX_STATUS_LENGTH}, but Revolt does not accept longer statuses, so {MAX_STATUS_LENGTH} is used instead"
+                ),
+            });
+        }
+        if self.ellipsis.chars().count() >= self.max_length {
+            invalid_values.push(InvalidValue {
+                severity: Severity::Warning,
+                key: "revolt.status.ellipsis",
+                message: String::from(
+                    "is not shorter than `max_length`, so truncated statuses are only the ellipsis",
+                ),
+            });
+        }
+        if let Some(idle) = &self.idle {
+            let length = idle.chars().count();
+            if length > MAX_STATUS_LENGTH {
+                invalid_values.push(InvalidValue {
+                    severity: Severity::Error,
+                    key: "revolt.status.idle",
+                    message: format!(
+                        "is {length} characters long, but Revolt does not accept statuses longer than {MAX_STATUS_LENGTH} characters"
+                    ),
+                });
+            }
+        }
+
+        invalid_values
+    }
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+impl Default for RevoltStatusOptions {
+    fn default() -> Self {
+        Self {
+            template: default_revolt_status_template(),
+            idle: None,
+            max_length: default_revolt_status_max_length(),
+            ellipsis: default_revolt_status_ellipsis(),
+            presence: RevoltPresenceOptions::default(),
+        }
+    }
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Clone, PartialEq, Eq)]
+pub struct RevoltPresenceOptions {
+    /// The presence to set while listening to something.
+    pub listening: Option<Presence>,
+    /// The presence to set while not listening to anything.
+    pub idle: Option<Presence>,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Presence {
+    /// Online.
+    Online,
+    /// Idle.
+    Idle,
+    /// Focus, only receive mentions.
+    Focus,
+    /// Busy, do not receive any notifications.
+    Busy,
+    /// Appear offline.
+    Invisible,
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+impl From<Presence> for rive_models::user::Presence {
+    fn from(presence: Presence) -> Self {
+        match presence {
+            Presence::Online => Self::Online,
+            Presence::Idle => Self::Idle,
+            Presence::Focus => Self::Focus,
+            Presence::Busy => Self::Busy,
+            Presence::Invisible => Self::Invisible,
+        }
+    }
+}
+
+/// Last.fm bans API keys that send too many requests.
+#[cfg(feature = "services-lastfm")]
+pub type LastFMCheckInterval = interval::CheckInterval<5000>;
+
+/// ListenBrainz rate limits requests by IP address.
+#[cfg(feature = "services-listenbrainz")]
+pub type ListenBrainzCheckInterval = interval::CheckInterval<1000>;
+
+#[cfg(feature = "services-lastfm")]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Default, Clone, PartialEq, Eq)]
+pub struct LastFMServiceOptions {
+    /// `Last.fm` username to check for listening activity.
+    pub username: String,
+    /// `Last.fm` API key to use for checking listening activity.
+    pub api_key: String,
+    /// Interval to check for listening activity, in seconds or as a
+    /// duration like `15s` or `2m`, but not fractional seconds. Must be at
+    /// least 5 seconds.
+    #[serde(default)]
+    pub check_interval: LastFMCheckInterval,
+}
+
+#[cfg(feature = "services-listenbrainz")]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct ListenBrainzServiceOptions {
+    /// `ListenBrainz` username to check for listening activity.
+    pub username: String,
+    /// `ListenBrainz` API URL to use for checking listening activity.
+    #[serde(default = "default_listenbrainz_api_url")]
+    pub api_url: String,
+    /// Interval to check for listening activity, in seconds or as a
+    /// duration like `15s` or `500ms`, but not fractional seconds. Must be
+    /// at least 1 second.
+    #[serde(default)]
+    pub check_interval: ListenBrainzCheckInterval,
+}
+
+#[cfg(feature = "services-listenbrainz")]
+impl Default for ListenBrainzServiceOptions {
+    fn default() -> Self {
+        Self {
+            username: String::default(),
+            api_url: default_listenbrainz_api_url(),
+            check_interval: interval::CheckInterval::default(),
+        }
+    }
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+fn default_revolt_status_template() -> String {
+    String::from("🎵 Listening to %NAME% by %ARTIST%")
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+const fn default_revolt_status_max_length() -> usize {
+    MAX_STATUS_LENGTH
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+fn default_revolt_status_ellipsis() -> String {
+    String::from("…")
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+fn default_revolt_profile_template() -> String {
+    String::from("🎵 Now playing: **%NAME%** by %ARTIST%")
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+fn default_revolt_profile_recent_track_template() -> String {
+    String::from("- %NAME% by %ARTIST%")
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+fn default_revolt_profile_start_marker() -> String {
+    String::from("[](#lure-start)")
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+fn default_revolt_profile_end_marker() -> String {
+    String::from("[](#lure-end)")
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+fn default_revolt_api_url() -> String {
+    String::from("https://api.revolt.chat")
+}
+
+#[cfg(feature = "services-listenbrainz")]
+fn default_listenbrainz_api_url() -> String {
+    String::from("https://api.listenbrainz.org")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an empty directory for a test.
+    fn test_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("lure-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).expect("test directory could not be created");
+
+        directory
+    }
+
+    #[test]
+    fn find_config_prefers_earlier_directories() {
+        let directory = test_directory("find-config");
+        let (xdg, system, current) = (
+            directory.join("xdg"),
+            directory.join("system"),
+            directory.join("current"),
+        );
+        for directory in [&xdg, &system, &current] {
+            std::fs::create_dir_all(directory).unwrap();
+        }
+        let directories = [xdg.clone(), system.clone(), current.clone()];
+
+        assert_eq!(find_config(&directories), None);
+
+        std::fs::write(current.join("config.yaml"), "").unwrap();
+        assert_eq!(find_config(&directories), Some(current.join("config.yaml")));
+
+        std::fs::write(system.join("config.toml"), "").unwrap();
+        assert_eq!(find_config(&directories), Some(system.join("config.toml")));
+
+        std::fs::write(xdg.join("config.json"), "").unwrap();
+        std::fs::write(xdg.join("config.yml"), "").unwrap();
+        assert_eq!(find_config(&directories), Some(xdg.join("config.yml")));
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn config_path_uses_the_given_path() {
+        assert_eq!(
+            config_path(Some(Path::new("some/config.toml"))),
+            PathBuf::from("some/config.toml")
+        );
+    }
+
+    #[test]
+    fn drop_in_paths_are_sorted_config_files() {
+        let directory = test_directory("drop-in");
+        let drop_in_directory = directory.join(DROP_IN_DIRECTORY);
+        std::fs::create_dir_all(&drop_in_directory).unwrap();
+        for name in ["20-b.toml", "10-a.yaml", "notes.txt"] {
+            std::fs::write(drop_in_directory.join(name), "").unwrap();
+        }
+
+        assert_eq!(
+            drop_in_paths(&directory.join("config.yaml")),
+            [
+                drop_in_directory.join("10-a.yaml"),
+                drop_in_directory.join("20-b.toml")
+            ]
+        );
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn validate_rejects_a_too_long_idle_status() {
+        let mut config = Config {
+            enable: None,
+            services: ServiceOptions::default(),
+            revolt: RevoltOptions::default(),
+            age_identity: None,
+        };
+        config.revolt.status.idle = Some("x".repeat(MAX_STATUS_LENGTH));
+        assert!(config.validate().is_ok());
+
+        config.revolt.status.idle = Some("x".repeat(MAX_STATUS_LENGTH + 1));
+        assert!(config.validate().is_err());
+    }
+}
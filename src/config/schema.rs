@@ -0,0 +1,96 @@
+//! JSON Schema of the configuration, for editors and validating configs.
+
+use schemars::{
+    gen::SchemaSettings,
+    schema::{
+        InstanceType, Metadata, ObjectValidation, RootSchema, Schema, SchemaObject,
+        SubschemaValidation,
+    },
+};
+
+use super::{secret::SecretSource, Config, SECRET_KEYS};
+
+/// Generates the schema of the configuration for the enabled services.
+pub fn generate() -> RootSchema {
+    let mut schema = SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<Config>();
+
+    add_secret_source_keys(&mut schema.schema);
+    for definition in schema.definitions.values_mut() {
+        if let Schema::Object(definition) = definition {
+            add_secret_source_keys(definition);
+        }
+    }
+
+    schema
+}
+
+/// Adds the suffixed variants of secret keys, which are read by
+/// `FileAdapter` and `SecretAdapter` instead of being deserialised. A
+/// required secret can be given with any of them.
+fn add_secret_source_keys(schema: &mut SchemaObject) {
+    let Some(object) = schema.object.as_mut() else {
+        return;
+    };
+
+    let mut required_secrets = Vec::new();
+    for key in SECRET_KEYS {
+        if !object.properties.contains_key(*key) {
+            continue;
+        }
+
+        let source_keys = SecretSource::ALL
+            .iter()
+            .map(|source| (*source, format!("{key}{}", source.suffix())))
+            .collect::<Vec<_>>();
+        if object.required.remove(*key) {
+            required_secrets.push(
+                std::iter::once(key.to_string())
+                    .chain(source_keys.iter().map(|(_, source_key)| source_key.clone()))
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        for (source, source_key) in source_keys {
+            object.properties.insert(
+                source_key,
+                Schema::Object(SchemaObject {
+                    metadata: Some(Box::new(Metadata {
+                        description: Some(source.description(key)),
+                        ..Default::default()
+                    })),
+                    instance_type: Some(InstanceType::String.into()),
+                    ..Default::default()
+                }),
+            );
+        }
+    }
+
+    for keys in required_secrets {
+        let any_of = keys
+            .into_iter()
+            .map(|key| {
+                Schema::Object(SchemaObject {
+                    object: Some(Box::new(ObjectValidation {
+                        required: [key].into(),
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        schema
+            .subschemas()
+            .all_of
+            .get_or_insert_with(Vec::new)
+            .push(Schema::Object(SchemaObject {
+                subschemas: Some(Box::new(SubschemaValidation {
+                    any_of: Some(any_of),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }));
+    }
+}
@@ -0,0 +1,300 @@
+//! Secrets read from outside the config, given by adding a suffix to a
+//! secret key, such as `session_token_command`.
+
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use figment::{
+    value::{Dict, Map, Value},
+    Error, Metadata, Profile, Provider,
+};
+
+use super::SECRET_KEYS;
+
+/// The service name of lure's entries in the system keyring.
+pub const KEYRING_SERVICE: &str = "lure";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretSource {
+    /// A file, read by `FileAdapter`.
+    File,
+    /// A credential in `$CREDENTIALS_DIRECTORY`, such as the ones systemd's
+    /// `LoadCredential=` provides.
+    Credential,
+    /// The first line of a command's output.
+    Command,
+    /// An entry in the system keyring.
+    Keyring,
+}
+
+impl SecretSource {
+    pub const ALL: &'static [Self] = &[Self::File, Self::Credential, Self::Command, Self::Keyring];
+
+    /// The suffix added to a secret key to read it from this source.
+    pub const fn suffix(self) -> &'static str {
+        match self {
+            Self::File => "_file",
+            Self::Credential => "_credential",
+            Self::Command => "_command",
+            Self::Keyring => "_keyring",
+        }
+    }
+
+    /// Describes the value of the suffixed `key`.
+    pub fn description(self, key: &str) -> String {
+        match self {
+            Self::File => format!("Path of a file to read `{key}` from."),
+            Self::Credential => {
+                format!("Name of the credential in `$CREDENTIALS_DIRECTORY` to read `{key}` from.")
+            }
+            Self::Command => format!(
+                "Command whose first line of output is used as `{key}`, such as `pass show lure/{key}`."
+            ),
+            Self::Keyring => format!(
+                "Username of the `{KEYRING_SERVICE}` entry in the system keyring to read `{key}` from."
+            ),
+        }
+    }
+
+    /// Describes where a secret given as `value` is read from.
+    pub fn describe(self, value: &str) -> String {
+        match self {
+            Self::File => format!("secret file `{value}`"),
+            Self::Credential => format!("credential `{value}`"),
+            Self::Command => format!("command `{value}`"),
+            Self::Keyring => format!("keyring entry `{value}`"),
+        }
+    }
+
+    fn read(self, value: &str) -> Result<String, String> {
+        match self {
+            Self::File => unreachable!("secret files are read by `FileAdapter`"),
+            Self::Credential => read_credential(value, None),
+            Self::Command => read_command(value),
+            Self::Keyring => read_keyring(value),
+        }
+    }
+}
+
+/// Reads the secrets of `provider` given with a [`SecretSource`] suffix,
+/// except `_file`. Only the secrets are provided.
+pub struct SecretAdapter<P>(pub P);
+
+impl<P: Provider> Provider for SecretAdapter<P> {
+    fn metadata(&self) -> Metadata {
+        self.0.metadata()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut data = Map::new();
+        for (profile, dict) in self.0.data()? {
+            let mut path = Vec::new();
+            let secrets = read_secrets(&dict, &mut path).map_err(|message| {
+                let mut error = Error::from(message);
+                error.path = path;
+                error
+            })?;
+
+            data.insert(profile, secrets);
+        }
+
+        Ok(data)
+    }
+}
+
+/// On error, `path` is left at the key that failed.
+fn read_secrets(dict: &Dict, path: &mut Vec<String>) -> Result<Dict, String> {
+    let mut secrets = Dict::new();
+
+    for (key, value) in dict {
+        path.push(key.clone());
+
+        if let Value::Dict(_, child) = value {
+            let child_secrets = read_secrets(child, path)?;
+            if !child_secrets.is_empty() {
+                secrets.insert(key.clone(), child_secrets.into());
+            }
+        }
+
+        for source in SecretSource::ALL {
+            let Some(secret_key) = key
+                .strip_suffix(source.suffix())
+                .filter(|secret_key| SECRET_KEYS.contains(secret_key))
+            else {
+                continue;
+            };
+            if *source == SecretSource::File {
+                continue;
+            }
+
+            let secret = scalar_string(value)
+                .ok_or_else(|| String::from("must be a string"))
+                .and_then(|value| source.read(&value))?;
+            secrets.insert(secret_key.to_string(), secret.into());
+        }
+
+        path.pop();
+    }
+
+    Ok(secrets)
+}
+
+/// Environment variables like `false` are parsed as other scalars, but are
+/// valid commands and names too.
+pub fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(_, value) => Some(value.clone()),
+        Value::Char(_, value) => Some(value.to_string()),
+        Value::Bool(_, value) => Some(value.to_string()),
+        Value::Num(_, number) => number
+            .to_u128()
+            .map(|number| number.to_string())
+            .or_else(|| number.to_i128().map(|number| number.to_string()))
+            .or_else(|| number.to_f64().map(|number| number.to_string())),
+        Value::Dict(..) | Value::Array(..) | Value::Empty(..) => None,
+    }
+}
+
+/// Reads the credential `name` from `directory`, or from
+/// `$CREDENTIALS_DIRECTORY` if not given. Trailing line breaks are removed,
+/// like from secret files.
+pub fn read_credential(name: &str, directory: Option<&Path>) -> Result<String, String> {
+    let directory = match directory {
+        Some(directory) => directory.to_path_buf(),
+        None => std::env::var_os("CREDENTIALS_DIRECTORY")
+            .map(Into::into)
+            .ok_or_else(|| {
+                String::from("`$CREDENTIALS_DIRECTORY` is not set, so credentials can't be read. It's set by systemd for services with `LoadCredential=`.")
+            })?,
+    };
+    let path = directory.join(name);
+
+    std::fs::read_to_string(&path)
+        .map(|secret| trim_secret(&secret).to_string())
+        .map_err(|error| format!("could not read credential `{}`: {error}", path.display()))
+}
+
+/// Removes the line breaks editors and `echo` add to the end of secrets.
+fn trim_secret(secret: &str) -> &str {
+    secret.trim_end_matches(['\r', '\n'])
+}
+
+fn read_command(command_line: &str) -> Result<String, String> {
+    #[cfg(unix)]
+    let mut command = {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(command_line);
+        command
+    };
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(command_line);
+        command
+    };
+
+    let output = command
+        .stdin(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|error| format!("could not run `{command_line}`: {error}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(match stderr.trim() {
+            "" => format!("`{command_line}` failed with {}", output.status),
+            stderr => format!("`{command_line}` failed with {}: {stderr}", output.status),
+        });
+    }
+
+    let output = String::from_utf8(output.stdout)
+        .map_err(|_| format!("output of `{command_line}` is not valid UTF-8"))?;
+    match output.lines().next() {
+        Some(secret) if !secret.is_empty() => Ok(secret.to_string()),
+        _ => Err(format!("`{command_line}` printed nothing")),
+    }
+}
+
+#[cfg(feature = "secrets-keyring")]
+fn read_keyring(username: &str) -> Result<String, String> {
+    keyring::Entry::new(KEYRING_SERVICE, username)
+        .and_then(|entry| entry.get_password())
+        .map_err(|error| format!("could not read keyring entry `{username}`: {error}"))
+}
+
+#[cfg(not(feature = "secrets-keyring"))]
+fn read_keyring(_: &str) -> Result<String, String> {
+    Err(String::from(
+        "lure is built without keyring support, rebuild it with the `secrets-keyring` feature",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use figment::{
+        providers::{Format, Yaml},
+        Figment,
+    };
+
+    use super::*;
+
+    #[test]
+    fn read_credential_trims_only_line_breaks() {
+        let directory =
+            std::env::temp_dir().join(format!("lure-credential-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("token"), " secret token \r\n").unwrap();
+
+        assert_eq!(
+            read_credential("token", Some(&directory)),
+            Ok(String::from(" secret token "))
+        );
+        assert!(read_credential("missing", Some(&directory)).is_err());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn secret_adapter_reads_suffixed_secret_keys() {
+        let figment = Figment::from(SecretAdapter(Yaml::string(
+            "revolt:\n  session_token_command: printf 'token\\nsecond line'\n  api_url_command: echo url\nservices:\n  lastfm:\n    api_key_command: echo key\n",
+        )));
+
+        assert_eq!(
+            figment.extract_inner::<String>("revolt.session_token"),
+            Ok(String::from("token"))
+        );
+        assert_eq!(
+            figment.extract_inner::<String>("services.lastfm.api_key"),
+            Ok(String::from("key"))
+        );
+        // Only secret keys are read from elsewhere.
+        assert!(figment.find_value("revolt.api_url").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn secret_adapter_reports_the_failing_key() {
+        let figment = Figment::from(SecretAdapter(Yaml::string(
+            "revolt:\n  bot_token_command: echo oops >&2; exit 3\n",
+        )));
+        let error = figment
+            .extract_inner::<String>("revolt.bot_token")
+            .unwrap_err();
+
+        assert_eq!(error.path, ["revolt", "bot_token_command"]);
+        assert!(error.to_string().contains("oops"));
+    }
+
+    #[test]
+    fn scalar_string_accepts_scalars() {
+        assert_eq!(
+            scalar_string(&Value::from(false)),
+            Some(String::from("false"))
+        );
+        assert_eq!(scalar_string(&Value::from(42)), Some(String::from("42")));
+        assert_eq!(scalar_string(&Value::from(Dict::new())), None);
+    }
+}
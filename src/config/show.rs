@@ -0,0 +1,92 @@
+//! The effective configuration, as lure sees it after merging every source.
+
+use std::path::Path;
+
+use figment::Figment;
+use serde_json::{Map, Value};
+
+use crate::utils::format::ConfigFormat;
+
+use super::{
+    describe_source,
+    secret::{self, SecretSource},
+    Config, SECRET_KEYS,
+};
+
+/// Shown instead of the values of secret keys.
+const REDACTED: &str = "<redacted>";
+
+pub struct EffectiveConfig {
+    /// The merged configuration, with secrets redacted.
+    pub config: Value,
+    /// Where each value comes from, keyed by its dotted path.
+    pub sources: Map<String, Value>,
+}
+
+impl EffectiveConfig {
+    pub fn load(path: Option<&Path>, format: Option<ConfigFormat>) -> anyhow::Result<Self> {
+        let figment = Config::figment(path, format);
+        let config = figment.extract::<Config>()?;
+
+        let mut effective_config = Self {
+            config: serde_json::to_value(&config)?,
+            sources: Map::new(),
+        };
+        let mut value = effective_config.config.take();
+        effective_config.annotate(&figment, &mut value, &mut Vec::new());
+        effective_config.config = value;
+
+        Ok(effective_config)
+    }
+
+    fn annotate(&mut self, figment: &Figment, value: &mut Value, path: &mut Vec<String>) {
+        if let Value::Object(map) = value {
+            for (key, value) in map.iter_mut() {
+                path.push(key.clone());
+                self.annotate(figment, value, path);
+                path.pop();
+            }
+
+            return;
+        }
+
+        let key = path.join(".");
+        let is_secret = path
+            .last()
+            .is_some_and(|last_key| SECRET_KEYS.contains(&last_key.as_str()));
+        if is_secret && !value.is_null() {
+            *value = Value::String(REDACTED.to_string());
+        }
+
+        let secret_source = SecretSource::ALL.iter().find_map(|source| {
+            let source_key = format!("{key}{}", source.suffix());
+            let value = figment.find_value(&source_key).ok()?;
+
+            Some((source, source_key, value))
+        });
+        let source = match secret_source {
+            Some((source, source_key, value)) if is_secret => {
+                let mut source_path = path.clone();
+                if let Some(last_key) = source_path.last_mut() {
+                    last_key.push_str(source.suffix());
+                }
+                let set_by = figment.find_metadata(&source_key).map_or_else(
+                    || "unknown source".to_string(),
+                    |metadata| describe_source(metadata, figment.profile(), &source_path),
+                );
+
+                format!(
+                    "{} (set by {set_by})",
+                    source.describe(&secret::scalar_string(&value).unwrap_or_default())
+                )
+            }
+            _ if figment.find_value(&key).is_ok() => figment.find_metadata(&key).map_or_else(
+                || "unknown source".to_string(),
+                |metadata| describe_source(metadata, figment.profile(), path),
+            ),
+            _ => "default".to_string(),
+        };
+
+        self.sources.insert(key, Value::String(source));
+    }
+}
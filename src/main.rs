@@ -14,6 +14,10 @@ async fn main() -> anyhow::Result<()> {
     match cli::Cli::parse().subcommand {
         #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
         cli::Subcommands::Start(start) => start.run().await,
+        #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+        cli::Subcommands::Now(now) => now.run().await,
         cli::Subcommands::Config(config) => config.run().await,
+        cli::Subcommands::Completions(completions) => completions.run().await,
+        cli::Subcommands::Manpage(manpage) => manpage.run().await,
     }
 }
@@ -0,0 +1,132 @@
+use tracing::{debug, trace};
+
+use crate::config::RevoltArtworkTarget;
+
+use super::{File, HttpClient, RevoltAPIError, UserEdit};
+
+pub struct Artwork {
+    target: RevoltArtworkTarget,
+    autumn_url: String,
+    original: Option<File>,
+    current_url: Option<String>,
+    /// The cover art URL added to the last edit, set once it's sent.
+    pending_url: Option<Option<String>>,
+}
+
+impl Artwork {
+    pub async fn try_new(
+        revolt_client: &HttpClient,
+        target: RevoltArtworkTarget,
+    ) -> anyhow::Result<Self, RevoltAPIError> {
+        trace!("fetching original {target:?} artwork");
+
+        let autumn_url = revolt_client.get_autumn_url().await?;
+        let user = revolt_client.get_user().await?;
+
+        let original_attachment = match target {
+            RevoltArtworkTarget::Avatar => user.avatar,
+            RevoltArtworkTarget::Background => {
+                revolt_client.get_profile(&user.id).await?.background
+            }
+        };
+        let original = match original_attachment {
+            Some(attachment) => Some(
+                revolt_client
+                    .download_file(&autumn_url, &attachment)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            target,
+            autumn_url,
+            original,
+            current_url: None,
+            pending_url: None,
+        })
+    }
+
+    /// Adds the cover art to `edit`. Call [`Self::applied`] once `edit` is
+    /// sent.
+    pub async fn show(
+        &mut self,
+        revolt_client: &HttpClient,
+        cover_art_url: Option<&str>,
+        edit: &mut UserEdit,
+    ) -> anyhow::Result<(), RevoltAPIError> {
+        self.pending_url = None;
+
+        let Some(cover_art_url) = cover_art_url else {
+            debug!("track has no cover art, restoring original artwork");
+            return self.restore(revolt_client, edit).await;
+        };
+
+        if self.current_url.as_deref() == Some(cover_art_url) {
+            debug!("cover art is the same as the current artwork, skipping artwork update");
+            return Ok(());
+        }
+
+        let file = revolt_client.download_cover_art(cover_art_url).await?;
+
+        self.set(revolt_client, Some(file), edit).await?;
+        self.pending_url = Some(Some(cover_art_url.to_string()));
+
+        Ok(())
+    }
+
+    /// Adds the original artwork to `edit`. Call [`Self::applied`] once
+    /// `edit` is sent.
+    pub async fn restore(
+        &mut self,
+        revolt_client: &HttpClient,
+        edit: &mut UserEdit,
+    ) -> anyhow::Result<(), RevoltAPIError> {
+        self.pending_url = None;
+
+        if self.current_url.is_none() {
+            return Ok(());
+        }
+
+        self.set(revolt_client, self.original.clone(), edit).await?;
+        self.pending_url = Some(None);
+
+        Ok(())
+    }
+
+    /// Records that the edit the artwork was last added to is sent.
+    pub fn applied(&mut self) {
+        if let Some(url) = self.pending_url.take() {
+            self.current_url = url;
+        }
+    }
+
+    async fn set(
+        &self,
+        revolt_client: &HttpClient,
+        file: Option<File>,
+        edit: &mut UserEdit,
+    ) -> anyhow::Result<(), RevoltAPIError> {
+        tracing::info!("updating Revolt {:?} artwork", self.target);
+
+        let tag = match self.target {
+            RevoltArtworkTarget::Avatar => "avatars",
+            RevoltArtworkTarget::Background => "backgrounds",
+        };
+        let id = match file {
+            Some(file) => Some(
+                revolt_client
+                    .upload_file(&self.autumn_url, tag, file)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        match self.target {
+            RevoltArtworkTarget::Avatar => edit.avatar(id),
+            RevoltArtworkTarget::Background => edit.background(id),
+        }
+
+        Ok(())
+    }
+}
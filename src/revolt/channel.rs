@@ -0,0 +1,156 @@
+use rive_models::{
+    data::{EditMessageData, SendMessageData},
+    embed::SendableEmbed,
+};
+use tokio::time;
+use tracing::{debug, trace};
+
+use crate::{
+    config::{RevoltChannelMode, RevoltChannelOptions},
+    services::TrackInfo,
+};
+
+use super::{HttpClient, RevoltAPIError};
+
+pub struct ChannelSink {
+    options: RevoltChannelOptions,
+    autumn_url: String,
+    message_id: Option<String>,
+    /// Whether the edited message is pinned, if known.
+    pinned: Option<bool>,
+}
+
+impl ChannelSink {
+    pub async fn try_new(
+        revolt_client: &HttpClient,
+        options: RevoltChannelOptions,
+    ) -> anyhow::Result<Self, RevoltAPIError> {
+        let autumn_url = revolt_client.get_autumn_url().await?;
+
+        Ok(Self {
+            message_id: options.message_id.clone(),
+            pinned: None,
+            options,
+            autumn_url,
+        })
+    }
+
+    pub async fn post(
+        &mut self,
+        revolt_client: &HttpClient,
+        track: &TrackInfo,
+    ) -> anyhow::Result<(), RevoltAPIError> {
+        trace!("posting track `{track:?}` to channel `{}`", self.options.id);
+
+        let media = match &track.cover_art_url {
+            Some(cover_art_url) => {
+                let file = revolt_client.download_cover_art(cover_art_url).await?;
+                Some(
+                    revolt_client
+                        .upload_file(&self.autumn_url, "attachments", file)
+                        .await?,
+                )
+            }
+            None => None,
+        };
+
+        let mut description = format!("by {}", track.artist);
+        if let Some(album) = &track.album {
+            description.push_str(&format!("\non {album}"));
+        }
+
+        let embed = SendableEmbed {
+            title: Some(track.name.clone()),
+            description: Some(description),
+            media,
+            ..Default::default()
+        };
+
+        loop {
+            let result = match (self.options.mode, &self.message_id) {
+                (RevoltChannelMode::Edit, Some(message_id)) => {
+                    revolt_client
+                        .edit_message(
+                            &self.options.id,
+                            message_id,
+                            &EditMessageData {
+                                content: None,
+                                embeds: Some(vec![embed.clone()]),
+                            },
+                        )
+                        .await
+                }
+                _ => revolt_client
+                    .send_message(
+                        &self.options.id,
+                        &SendMessageData {
+                            embeds: Some(vec![embed.clone()]),
+                            ..Default::default()
+                        },
+                    )
+                    .await
+                    .map(|message_id| {
+                        if self.options.mode == RevoltChannelMode::Edit {
+                            self.message_id = Some(message_id);
+                            self.pinned = Some(false);
+                        }
+                    }),
+            };
+
+            match result {
+                Ok(()) => break,
+                Err(RevoltAPIError::RateLimitExceeded(remaining)) => {
+                    tracing::warn!("rate limit exceeded, waiting until the time limit is over to post the track...");
+                    time::sleep(time::Duration::from_millis(
+                        remaining.try_into().map_err(anyhow::Error::from)?,
+                    ))
+                    .await;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        debug!("posted track to channel `{}`", self.options.id);
+
+        if self.options.mode == RevoltChannelMode::Edit {
+            self.pin(revolt_client).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Pins the edited message if it isn't pinned yet. A configured
+    /// message is checked once, a sent one is pinned right away.
+    async fn pin(&mut self, revolt_client: &HttpClient) -> anyhow::Result<(), RevoltAPIError> {
+        let Some(message_id) = &self.message_id else {
+            return Ok(());
+        };
+
+        loop {
+            let result = match self.pinned {
+                Some(true) => return Ok(()),
+                Some(false) => revolt_client
+                    .pin_message(&self.options.id, message_id)
+                    .await
+                    .map(|()| true),
+                None => {
+                    revolt_client
+                        .is_message_pinned(&self.options.id, message_id)
+                        .await
+                }
+            };
+
+            match result {
+                Ok(pinned) => self.pinned = Some(pinned),
+                Err(RevoltAPIError::RateLimitExceeded(remaining)) => {
+                    tracing::warn!("rate limit exceeded, waiting until the time limit is over to pin the message...");
+                    time::sleep(time::Duration::from_millis(
+                        remaining.try_into().map_err(anyhow::Error::from)?,
+                    ))
+                    .await;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Changes that are logged instead of sent, for `lure start --dry-run`.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use super::RevoltAPIError;
+
+/// The window of Revolt's rate limits.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(10);
+
+/// A rate limit bucket of the Revolt API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bucket {
+    /// Edits of the user, such as the status.
+    UserEdit,
+    /// Sent messages.
+    Messaging,
+    /// Other channel requests, such as edited messages.
+    Channels,
+    /// Uploads to Autumn, the file server. Its limit isn't documented, so
+    /// the default limit of the API is assumed.
+    Autumn,
+}
+
+impl Bucket {
+    /// How many requests Revolt allows in [`RATE_LIMIT_WINDOW`].
+    const fn limit(self) -> usize {
+        match self {
+            Self::UserEdit => 2,
+            Self::Messaging => 10,
+            Self::Channels => 15,
+            Self::Autumn => 20,
+        }
+    }
+}
+
+/// Simulates the rate limits of the requests that aren't sent.
+#[derive(Debug, Default)]
+pub struct DryRun {
+    requests: Mutex<HashMap<Bucket, VecDeque<Instant>>>,
+}
+
+impl DryRun {
+    /// Records a request in `bucket`, or fails like Revolt would if the
+    /// bucket is full.
+    pub fn request(&self, bucket: Bucket) -> Result<(), RevoltAPIError> {
+        let now = Instant::now();
+        let mut requests = self
+            .requests
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let requests = requests.entry(bucket).or_default();
+
+        while requests
+            .front()
+            .is_some_and(|request| now.duration_since(*request) >= RATE_LIMIT_WINDOW)
+        {
+            requests.pop_front();
+        }
+
+        if requests.len() >= bucket.limit() {
+            let oldest = requests.front().expect("a full bucket has requests");
+            let remaining = RATE_LIMIT_WINDOW.saturating_sub(now.duration_since(*oldest));
+            tracing::info!("dry run: simulating the `{bucket:?}` rate limit for {remaining:?}");
+
+            return Err(RevoltAPIError::RateLimitExceeded(remaining.as_millis()));
+        }
+
+        requests.push_back(now);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::revolt::{File, HttpClient};
+
+    #[test]
+    fn full_buckets_are_rate_limited() {
+        let dry_run = DryRun::default();
+        for _ in 0..Bucket::UserEdit.limit() {
+            assert!(dry_run.request(Bucket::UserEdit).is_ok());
+        }
+
+        let Err(RevoltAPIError::RateLimitExceeded(remaining)) = dry_run.request(Bucket::UserEdit)
+        else {
+            panic!("the full bucket is not rate limited");
+        };
+        assert!(remaining <= RATE_LIMIT_WINDOW.as_millis());
+        // Buckets are limited separately.
+        assert!(dry_run.request(Bucket::Messaging).is_ok());
+    }
+
+    #[test]
+    fn requests_expire_after_the_window() {
+        let dry_run = DryRun::default();
+        let expired = Instant::now() - RATE_LIMIT_WINDOW;
+        dry_run.requests.lock().unwrap().insert(
+            Bucket::UserEdit,
+            VecDeque::from(vec![expired; Bucket::UserEdit.limit()]),
+        );
+
+        assert!(dry_run.request(Bucket::UserEdit).is_ok());
+        assert_eq!(dry_run.requests.lock().unwrap()[&Bucket::UserEdit].len(), 1);
+    }
+
+    #[tokio::test]
+    async fn uploads_are_recorded_in_the_autumn_bucket() {
+        let client = HttpClient::default().with_dry_run(true);
+        let upload = || {
+            client.upload_file(
+                "https://autumn.revolt.chat",
+                "avatars",
+                File {
+                    filename: String::from("cover.png"),
+                    content_type: String::from("image/png"),
+                    bytes: Vec::new(),
+                },
+            )
+        };
+
+        for _ in 0..Bucket::Autumn.limit() {
+            assert_eq!(upload().await.unwrap(), "dry-run");
+        }
+        assert!(matches!(
+            upload().await,
+            Err(RevoltAPIError::RateLimitExceeded(_))
+        ));
+        // Edits of the user are limited separately.
+        let dry_run = client.dry_run.as_ref().unwrap();
+        assert!(dry_run.request(Bucket::UserEdit).is_ok());
+    }
+}
@@ -0,0 +1,721 @@
+#![cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+
+pub mod artwork;
+pub mod channel;
+pub mod dry_run;
+pub mod profile;
+pub mod status;
+
+mod models;
+
+use std::str::FromStr;
+
+use reqwest::{
+    header::{HeaderName, HeaderValue, CONTENT_TYPE},
+    multipart::{Form, Part},
+    Client, RequestBuilder, StatusCode,
+};
+use rive_models::{
+    attachment::Attachment,
+    authentication::Authentication,
+    autumn::UploadData,
+    core::InstanceConfiguration,
+    data::{EditMessageData, SendMessageData},
+    message::Message,
+    session::SessionInfo,
+    user::{FieldsUser, Presence, User, UserProfile, UserStatus},
+};
+use serde::Deserialize;
+use tracing::trace;
+
+use crate::config::{secret, RevoltAuthentication, RevoltOptions, RevoltTokenType};
+
+#[derive(thiserror::Error, Debug)]
+pub enum RevoltAPIError {
+    #[error("Revolt API authentication failed. Please check your credentials.")]
+    AuthenticationFailed,
+    #[error("Revolt session token is invalid or expired. Run `lure config revolt get-session-token` to obtain a new one.")]
+    InvalidSessionToken,
+    #[error("Revolt bot token is invalid. Please check the token in your bot's settings.")]
+    InvalidBotToken,
+    #[error("Revolt token type does not match the account: {0}")]
+    TokenTypeMismatch(&'static str),
+    #[error("Revolt API rate limit exceeded.")]
+    RateLimitExceeded(u128),
+    #[error("Revolt API rejected the request: {0}")]
+    BadRequest(String),
+    #[error("Revolt API rejected the status update ({0}). The status text may be too long or contain invalid characters.")]
+    InvalidStatus(String),
+    #[error("Revolt API returned an unexpected error: {0}")]
+    Unknown(StatusCode),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Clone)]
+pub enum PresenceUpdate {
+    Keep,
+    Set(Presence),
+    Remove,
+}
+
+impl From<Option<Presence>> for PresenceUpdate {
+    fn from(presence: Option<Presence>) -> Self {
+        presence.map_or(Self::Remove, Self::Set)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct File {
+    pub filename: String,
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Changes to the account, sent together with [`HttpClient::edit_user`].
+/// Revolt only allows two user edits in 10 seconds, so the status, artwork
+/// and profile content are changed in one request.
+#[derive(Debug, Default)]
+pub struct UserEdit {
+    data: models::users::edit_user::Data,
+}
+
+impl UserEdit {
+    /// Sets the status text, or removes it if `text` is `None`.
+    pub fn status(&mut self, text: Option<String>, presence: PresenceUpdate) {
+        tracing::info!("updating Revolt status to {:?} ({:?})", &text, &presence);
+
+        let mut status = UserStatus::default();
+        match text {
+            Some(text) => status.text = Some(text),
+            None => self.remove(FieldsUser::StatusText),
+        }
+        match presence {
+            PresenceUpdate::Keep => {}
+            PresenceUpdate::Set(presence) => status.presence = Some(presence),
+            PresenceUpdate::Remove => self.remove(FieldsUser::StatusPresence),
+        }
+
+        self.data.status = (status.text.is_some() || status.presence.is_some()).then_some(status);
+    }
+
+    /// Sets the avatar to an uploaded file, or removes it.
+    pub fn avatar(&mut self, id: Option<String>) {
+        match id {
+            Some(id) => self.data.avatar = Some(id),
+            None => self.remove(FieldsUser::Avatar),
+        }
+    }
+
+    /// Sets the profile background to an uploaded file, or removes it.
+    pub fn background(&mut self, id: Option<String>) {
+        match id {
+            Some(id) => self.profile().background = Some(id),
+            None => self.remove(FieldsUser::ProfileBackground),
+        }
+    }
+
+    /// Sets the profile content, or removes it if it's empty.
+    pub fn profile_content(&mut self, content: Option<String>) {
+        match content.filter(|content| !content.is_empty()) {
+            Some(content) => self.profile().content = Some(content),
+            None => self.remove(FieldsUser::ProfileContent),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let models::users::edit_user::Data {
+            status,
+            profile,
+            avatar,
+            remove,
+        } = &self.data;
+
+        status.is_none() && profile.is_none() && avatar.is_none() && remove.is_none()
+    }
+
+    fn profile(&mut self) -> &mut models::users::edit_user::Profile {
+        self.data.profile.get_or_insert_with(Default::default)
+    }
+
+    fn remove(&mut self, field: FieldsUser) {
+        self.data.remove.get_or_insert_with(Vec::new).push(field);
+    }
+}
+
+pub struct HttpClient {
+    /// Sends the authentication header only with requests to Revolt, so it
+    /// can also download cover art.
+    client: reqwest::Client,
+    base_url: String,
+    authentication: Authentication,
+    authentication_header: Option<(HeaderName, HeaderValue)>,
+    /// Set if changes are logged instead of sent.
+    dry_run: Option<dry_run::DryRun>,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: String::from("https://api.revolt.chat"),
+            authentication: Authentication::None,
+            authentication_header: None,
+            dry_run: None,
+        }
+    }
+}
+
+impl HttpClient {
+    pub fn try_new(api_url: String, authentication: &Authentication) -> anyhow::Result<Self> {
+        let authentication_header = match authentication {
+            Authentication::None => None,
+            authentication => Some((
+                HeaderName::from_str(&authentication.header_key())?,
+                HeaderValue::from_str(&authentication.value())?,
+            )),
+        };
+
+        Ok(Self {
+            client: Client::builder().build()?,
+            base_url: api_url,
+            authentication: authentication.clone(),
+            authentication_header,
+            dry_run: None,
+        })
+    }
+
+    pub fn try_from_options(options: &RevoltOptions) -> anyhow::Result<Self> {
+        Self::try_new(options.api_url.clone(), &authentication(options)?)
+    }
+
+    /// Logs changes instead of sending them, simulating the rate limits of
+    /// Revolt. Data is still fetched from Revolt.
+    #[must_use]
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run.then(dry_run::DryRun::default);
+        self
+    }
+
+    pub const fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
+    }
+
+    fn get(&self, url: String) -> RequestBuilder {
+        self.authenticated(self.client.get(url))
+    }
+
+    fn post(&self, url: String) -> RequestBuilder {
+        self.authenticated(self.client.post(url))
+    }
+
+    fn patch(&self, url: String) -> RequestBuilder {
+        self.authenticated(self.client.patch(url))
+    }
+
+    fn delete(&self, url: String) -> RequestBuilder {
+        self.authenticated(self.client.delete(url))
+    }
+
+    fn authenticated(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.authentication_header {
+            Some((name, value)) => request.header(name, value),
+            None => request,
+        }
+    }
+
+    /// Sends the changes of `edit` as one request.
+    pub async fn edit_user(&self, edit: &UserEdit) -> anyhow::Result<(), RevoltAPIError> {
+        trace!("editing user data (`edit_user`)...");
+
+        if let Some(dry_run) = &self.dry_run {
+            dry_run.request(dry_run::Bucket::UserEdit)?;
+            tracing::info!("dry run: not editing user data {}", json_string(&edit.data));
+
+            return Ok(());
+        }
+
+        self.patch(format!("{}/users/@me", self.base_url))
+            .json(&edit.data)
+            .send()
+            .await?
+            .handle_return_error()
+            .await
+            .map_err(|error| match error {
+                RevoltAPIError::BadRequest(reason) if edit.data.status.is_some() => {
+                    RevoltAPIError::InvalidStatus(reason)
+                }
+                error => error,
+            })?;
+
+        tracing::debug!("updated Revolt user data");
+
+        Ok(())
+    }
+
+    pub async fn get_user(&self) -> anyhow::Result<User, RevoltAPIError> {
+        trace!("fetching user data from Revolt API (`get_user`)...");
+
+        let user = self
+            .get(format!("{}/users/@me", self.base_url))
+            .send()
+            .await?
+            .handle_return_error()
+            .await?
+            .json()
+            .await?;
+
+        Ok(user)
+    }
+
+    pub async fn get_profile(&self, user_id: &str) -> anyhow::Result<UserProfile, RevoltAPIError> {
+        trace!("fetching user profile from Revolt API (`get_profile`)...");
+
+        let profile = self
+            .get(format!("{}/users/{user_id}/profile", self.base_url))
+            .send()
+            .await?
+            .handle_return_error()
+            .await?
+            .json()
+            .await?;
+
+        Ok(profile)
+    }
+
+    pub async fn send_message(
+        &self,
+        channel_id: &str,
+        data: &SendMessageData,
+    ) -> anyhow::Result<String, RevoltAPIError> {
+        trace!("sending message to channel `{channel_id}`...");
+
+        if let Some(dry_run) = &self.dry_run {
+            dry_run.request(dry_run::Bucket::Messaging)?;
+            tracing::info!(
+                "dry run: not sending message {} to channel `{channel_id}`",
+                json_string(data)
+            );
+
+            return Ok(String::from("dry-run"));
+        }
+
+        let message: Message = self
+            .post(format!("{}/channels/{channel_id}/messages", self.base_url))
+            .json(data)
+            .send()
+            .await?
+            .handle_return_error()
+            .await?
+            .json()
+            .await?;
+
+        Ok(message.id)
+    }
+
+    pub async fn edit_message(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        data: &EditMessageData,
+    ) -> anyhow::Result<(), RevoltAPIError> {
+        trace!("editing message `{message_id}` in channel `{channel_id}`...");
+
+        if let Some(dry_run) = &self.dry_run {
+            dry_run.request(dry_run::Bucket::Channels)?;
+            tracing::info!(
+                "dry run: not editing message `{message_id}` in channel `{channel_id}` to {}",
+                json_string(data)
+            );
+
+            return Ok(());
+        }
+
+        self.patch(format!(
+            "{}/channels/{channel_id}/messages/{message_id}",
+            self.base_url
+        ))
+        .json(data)
+        .send()
+        .await?
+        .handle_return_error()
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn is_message_pinned(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+    ) -> anyhow::Result<bool, RevoltAPIError> {
+        trace!("fetching message `{message_id}` in channel `{channel_id}`...");
+
+        let message: models::channels::fetch_message::Data = self
+            .get(format!(
+                "{}/channels/{channel_id}/messages/{message_id}",
+                self.base_url
+            ))
+            .send()
+            .await?
+            .handle_return_error()
+            .await?
+            .json()
+            .await?;
+
+        Ok(message.pinned)
+    }
+
+    pub async fn pin_message(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+    ) -> anyhow::Result<(), RevoltAPIError> {
+        trace!("pinning message `{message_id}` in channel `{channel_id}`...");
+
+        if let Some(dry_run) = &self.dry_run {
+            dry_run.request(dry_run::Bucket::Channels)?;
+            tracing::info!("dry run: not pinning message `{message_id}` in channel `{channel_id}`");
+
+            return Ok(());
+        }
+
+        self.post(format!(
+            "{}/channels/{channel_id}/messages/{message_id}/pin",
+            self.base_url
+        ))
+        .send()
+        .await?
+        .handle_return_error()
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_sessions(&self) -> anyhow::Result<Vec<SessionInfo>, RevoltAPIError> {
+        trace!("fetching sessions from Revolt API (`get_sessions`)...");
+
+        let sessions = self
+            .get(format!("{}/auth/session/all", self.base_url))
+            .send()
+            .await?
+            .handle_return_error()
+            .await?
+            .json()
+            .await?;
+
+        Ok(sessions)
+    }
+
+    pub async fn revoke_session(&self, session_id: &str) -> anyhow::Result<(), RevoltAPIError> {
+        trace!("revoking session `{session_id}`...");
+
+        self.delete(format!("{}/auth/session/{session_id}", self.base_url))
+            .send()
+            .await?
+            .handle_return_error()
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn logout(&self) -> anyhow::Result<(), RevoltAPIError> {
+        trace!("logging out of the current session...");
+
+        self.post(format!("{}/auth/session/logout", self.base_url))
+            .send()
+            .await?
+            .handle_return_error()
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_autumn_url(&self) -> anyhow::Result<String, RevoltAPIError> {
+        trace!("fetching instance configuration from Revolt API (`get_autumn_url`)...");
+
+        let configuration: InstanceConfiguration = self
+            .get(format!("{}/", self.base_url))
+            .send()
+            .await?
+            .handle_return_error()
+            .await?
+            .json()
+            .await?;
+
+        if !configuration.features.autumn.enabled {
+            return Err(
+                anyhow::anyhow!("Autumn file server is not enabled on this instance.").into(),
+            );
+        }
+
+        Ok(configuration.features.autumn.url)
+    }
+
+    pub async fn upload_file(
+        &self,
+        autumn_url: &str,
+        tag: &str,
+        file: File,
+    ) -> anyhow::Result<String, RevoltAPIError> {
+        trace!("uploading `{}` to Autumn tag `{tag}`...", file.filename);
+
+        if let Some(dry_run) = &self.dry_run {
+            dry_run.request(dry_run::Bucket::Autumn)?;
+            tracing::info!(
+                "dry run: not uploading `{}` to Autumn tag `{tag}`",
+                file.filename
+            );
+
+            return Ok(String::from("dry-run"));
+        }
+
+        let part = Part::bytes(file.bytes)
+            .file_name(file.filename)
+            .mime_str(&file.content_type)?;
+
+        let upload_data: UploadData = self
+            .post(format!("{autumn_url}/{tag}"))
+            .multipart(Form::new().part("file", part))
+            .send()
+            .await?
+            .handle_return_error()
+            .await?
+            .json()
+            .await?;
+
+        Ok(upload_data.id)
+    }
+
+    pub async fn download_file(
+        &self,
+        autumn_url: &str,
+        attachment: &Attachment,
+    ) -> anyhow::Result<File, RevoltAPIError> {
+        trace!("downloading `{}` from Autumn...", attachment.filename);
+
+        let bytes = self
+            .get(format!("{autumn_url}/{}/{}", attachment.tag, attachment.id))
+            .send()
+            .await?
+            .handle_return_error()
+            .await?
+            .bytes()
+            .await?;
+
+        Ok(File {
+            filename: attachment.filename.clone(),
+            content_type: attachment.content_type.clone(),
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    /// Downloads cover art, without the Revolt credentials.
+    pub async fn download_cover_art(
+        &self,
+        cover_art_url: &str,
+    ) -> anyhow::Result<File, RevoltAPIError> {
+        trace!("downloading cover art from `{cover_art_url}`...");
+
+        let response = self
+            .client
+            .get(cover_art_url)
+            .send()
+            .await?
+            .error_for_status()?;
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("image/jpeg")
+            .to_string();
+        let filename = cover_art_url
+            .rsplit('/')
+            .next()
+            .filter(|filename| !filename.is_empty())
+            .unwrap_or("cover")
+            .to_string();
+
+        Ok(File {
+            filename,
+            content_type,
+            bytes: response.bytes().await?.to_vec(),
+        })
+    }
+
+    pub async fn get_status(&self) -> anyhow::Result<UserStatus, RevoltAPIError> {
+        trace!("fetching user data from Revolt API (`get_status`)...");
+
+        let response = self
+            .get(format!("{}/users/@me", self.base_url))
+            .send()
+            .await?
+            .handle_return_error()
+            .await?;
+
+        let user_data: User = response.json().await?;
+        let status = user_data.status.unwrap_or_default();
+
+        trace!("successfully fetched the Revolt status");
+
+        Ok(status)
+    }
+
+    pub async fn ping(&self) -> anyhow::Result<(), RevoltAPIError> {
+        trace!("fetching user data from Revolt API (`ping`)...");
+
+        let user = self.get_user().await.map_err(|error| match error {
+            RevoltAPIError::AuthenticationFailed => match self.authentication {
+                Authentication::SessionToken(_) => RevoltAPIError::InvalidSessionToken,
+                Authentication::BotToken(_) => RevoltAPIError::InvalidBotToken,
+                _ => error,
+            },
+            error => error,
+        })?;
+
+        match (&self.authentication, user.bot.is_some()) {
+            (Authentication::SessionToken(_), true) => {
+                return Err(RevoltAPIError::TokenTypeMismatch(
+                    "a session token is configured, but the account is a bot. Use the `bot_token` authentication type instead.",
+                ));
+            }
+            (Authentication::BotToken(_), false) => {
+                return Err(RevoltAPIError::TokenTypeMismatch(
+                    "a bot token is configured, but the account is not a bot. Use the `session_token` authentication type instead.",
+                ));
+            }
+            _ => {}
+        }
+
+        trace!("successfully pinged the Revolt API");
+
+        Ok(())
+    }
+}
+
+pub fn authentication(options: &RevoltOptions) -> anyhow::Result<Authentication> {
+    match &options.authentication {
+        RevoltAuthentication::SessionToken => options
+            .session_token
+            .clone()
+            .map(Authentication::SessionToken)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "`session_token` authentication is used, but no `revolt.session_token` is provided."
+                )
+            }),
+        RevoltAuthentication::BotToken => options
+            .bot_token
+            .clone()
+            .map(Authentication::BotToken)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "`bot_token` authentication is used, but no `revolt.bot_token` is provided."
+                )
+            }),
+        RevoltAuthentication::Credential {
+            name,
+            token_type,
+            directory,
+        } => {
+            let token = secret::read_credential(name, directory.as_deref()).map_err(|error| {
+                anyhow::anyhow!("`credential` authentication is used, but the token can't be read: {error}")
+            })?;
+
+            Ok(match token_type {
+                RevoltTokenType::SessionToken => Authentication::SessionToken(token),
+                RevoltTokenType::BotToken => Authentication::BotToken(token),
+            })
+        }
+    }
+}
+
+fn json_string(data: &impl serde::Serialize) -> String {
+    serde_json::to_string(data).unwrap_or_else(|error| format!("<{error}>"))
+}
+
+impl From<reqwest::Error> for RevoltAPIError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Other(error.into())
+    }
+}
+
+trait ResponseExt: Sized {
+    async fn handle_return_error(self) -> anyhow::Result<Self, RevoltAPIError>;
+}
+
+impl ResponseExt for reqwest::Response {
+    async fn handle_return_error(self) -> anyhow::Result<Self, RevoltAPIError> {
+        match self.status() {
+            StatusCode::OK | StatusCode::NO_CONTENT => Ok(self),
+            StatusCode::UNAUTHORIZED => Err(RevoltAPIError::AuthenticationFailed),
+            StatusCode::TOO_MANY_REQUESTS => {
+                let retry_after = self
+                    .headers()
+                    .get("X-Ratelimit-Reset-After")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse::<u128>().ok())
+                    .unwrap_or(0);
+
+                Err(RevoltAPIError::RateLimitExceeded(retry_after))
+            }
+            StatusCode::BAD_REQUEST => {
+                #[derive(Deserialize)]
+                struct JSONError {
+                    #[serde(rename = "type")]
+                    error_type: String,
+                    error: Option<String>,
+                }
+
+                let reason = match self.json::<JSONError>().await {
+                    Ok(JSONError {
+                        error_type,
+                        error: Some(error),
+                    }) => format!("{error_type}: {error}"),
+                    Ok(JSONError { error_type, .. }) => error_type,
+                    Err(_) => String::from("no details were provided"),
+                };
+
+                Err(RevoltAPIError::BadRequest(reason))
+            }
+            status => Err(RevoltAPIError::Unknown(status)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_edit_combines_all_changes() {
+        let mut edit = UserEdit::default();
+        assert!(edit.is_empty());
+
+        edit.status(Some(String::from("Listening")), PresenceUpdate::Remove);
+        edit.background(Some(String::from("background")));
+        edit.profile_content(Some(String::from("content")));
+        edit.avatar(None);
+
+        assert_eq!(
+            serde_json::to_value(&edit.data).unwrap(),
+            serde_json::json!({
+                "status": { "text": "Listening" },
+                "profile": { "content": "content", "background": "background" },
+                "remove": ["StatusPresence", "Avatar"],
+            })
+        );
+    }
+
+    #[test]
+    fn user_edit_removes_empty_values() {
+        let mut edit = UserEdit::default();
+        edit.status(None, PresenceUpdate::Keep);
+        edit.profile_content(Some(String::new()));
+
+        assert_eq!(
+            serde_json::to_value(&edit.data).unwrap(),
+            serde_json::json!({ "remove": ["StatusText", "ProfileContent"] })
+        );
+    }
+}
@@ -0,0 +1,41 @@
+pub mod users {
+    pub mod edit_user {
+        use rive_models::user::{FieldsUser, UserStatus};
+        use serde::Serialize;
+
+        // `rive_models::data::EditUserData` can't be used for profile
+        // changes, since the fields of its profile data are private.
+        #[derive(Serialize, Debug, Default)]
+        pub struct Data {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub status: Option<UserStatus>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub profile: Option<Profile>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub avatar: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub remove: Option<Vec<FieldsUser>>,
+        }
+
+        #[derive(Serialize, Debug, Default)]
+        pub struct Profile {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub content: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub background: Option<String>,
+        }
+    }
+}
+
+pub mod channels {
+    pub mod fetch_message {
+        use serde::Deserialize;
+
+        // `rive_models::message::Message` has no `pinned` field.
+        #[derive(Deserialize, Debug)]
+        pub struct Data {
+            #[serde(default)]
+            pub pinned: bool,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::VecDeque;
+
+use tracing::trace;
+
+use crate::{config::RevoltProfileOptions, services::TrackInfo};
+
+use super::{status, HttpClient, RevoltAPIError, UserEdit};
+
+/// The maximum profile content length accepted by Revolt.
+const MAX_PROFILE_CONTENT_LENGTH: usize = 2000;
+
+pub struct ProfileSection {
+    options: RevoltProfileOptions,
+    original: Option<String>,
+    current_track: Option<TrackInfo>,
+    recent_tracks: VecDeque<TrackInfo>,
+    changed: bool,
+}
+
+impl ProfileSection {
+    pub async fn try_new(
+        revolt_client: &HttpClient,
+        options: RevoltProfileOptions,
+    ) -> anyhow::Result<Self, RevoltAPIError> {
+        trace!("fetching original profile content");
+
+        let user = revolt_client.get_user().await?;
+        let original = revolt_client.get_profile(&user.id).await?.content;
+
+        Ok(Self {
+            options,
+            original,
+            current_track: None,
+            recent_tracks: VecDeque::new(),
+            changed: false,
+        })
+    }
+
+    /// Adds the profile content for `track` to `edit`.
+    pub fn show(&mut self, track: Option<&TrackInfo>, edit: &mut UserEdit) {
+        if self.current_track.as_ref() != track {
+            if let Some(previous_track) = self.current_track.take() {
+                self.recent_tracks.push_front(previous_track);
+                self.recent_tracks.truncate(self.options.recent_tracks);
+            }
+            self.current_track = track.cloned();
+        }
+
+        tracing::info!("updating Revolt profile content");
+        edit.profile_content(Some(self.content()));
+        self.changed = true;
+    }
+
+    /// Adds the original profile content to `edit`, if it was changed.
+    pub fn restore(&mut self, edit: &mut UserEdit) {
+        if !self.changed {
+            return;
+        }
+
+        tracing::info!("restoring Revolt profile content");
+        edit.profile_content(self.original.clone());
+        self.changed = false;
+    }
+
+    fn content(&self) -> String {
+        let mut lines = Vec::new();
+        match &self.current_track {
+            Some(track) => lines.push(status::fill(&self.options.template, track)),
+            None => lines.extend(self.options.idle.clone()),
+        }
+        lines.extend(
+            self.recent_tracks
+                .iter()
+                .map(|track| status::fill(&self.options.recent_track_template, track)),
+        );
+
+        let block = lines.join("\n");
+        let content = self.replace_section(&block);
+
+        let overflow = content
+            .chars()
+            .count()
+            .saturating_sub(MAX_PROFILE_CONTENT_LENGTH);
+        if overflow == 0 {
+            return content;
+        }
+
+        let block_length = block.chars().count().saturating_sub(overflow);
+        self.replace_section(&status::truncate(&block, block_length, "…"))
+    }
+
+    fn replace_section(&self, block: &str) -> String {
+        let RevoltProfileOptions {
+            start_marker,
+            end_marker,
+            ..
+        } = &self.options;
+        let original = self.original.as_deref().unwrap_or_default();
+        let section = format!("{start_marker}\n{block}\n{end_marker}");
+
+        match (original.find(start_marker), original.find(end_marker)) {
+            (Some(start), Some(end)) if start < end => format!(
+                "{}{section}{}",
+                &original[..start],
+                &original[end + end_marker.len()..]
+            ),
+            _ if original.trim().is_empty() => section,
+            _ => format!("{}\n\n{section}", original.trim_end()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(original: Option<&str>, recent_tracks: usize) -> ProfileSection {
+        ProfileSection {
+            options: RevoltProfileOptions {
+                template: String::from("Now: %NAME% by %ARTIST%"),
+                idle: Some(String::from("Nothing")),
+                recent_tracks,
+                recent_track_template: String::from("- %NAME%"),
+                start_marker: String::from("<start>"),
+                end_marker: String::from("<end>"),
+            },
+            original: original.map(String::from),
+            current_track: None,
+            recent_tracks: VecDeque::new(),
+            changed: false,
+        }
+    }
+
+    fn track(name: &str) -> TrackInfo {
+        TrackInfo {
+            artist: String::from("Artist"),
+            name: name.to_string(),
+            album: None,
+            cover_art_url: None,
+        }
+    }
+
+    #[test]
+    fn replace_section_replaces_only_between_the_markers() {
+        let section = section(Some("Hi!\n<start>\nold\n<end>\nBye!"), 0);
+
+        assert_eq!(
+            section.replace_section("new"),
+            "Hi!\n<start>\nnew\n<end>\nBye!"
+        );
+    }
+
+    #[test]
+    fn replace_section_appends_to_content_without_markers() {
+        assert_eq!(
+            section(Some("Hi!\n\n"), 0).replace_section("new"),
+            "Hi!\n\n<start>\nnew\n<end>"
+        );
+        assert_eq!(
+            section(Some("<end> before <start>"), 0).replace_section("new"),
+            "<end> before <start>\n\n<start>\nnew\n<end>"
+        );
+        assert_eq!(
+            section(None, 0).replace_section("new"),
+            "<start>\nnew\n<end>"
+        );
+    }
+
+    #[test]
+    fn content_lists_recent_tracks() {
+        let mut section = section(None, 1);
+        let mut edit = UserEdit::default();
+        section.show(Some(&track("One")), &mut edit);
+        section.show(Some(&track("Two")), &mut edit);
+        section.show(Some(&track("Three")), &mut edit);
+
+        assert_eq!(
+            section.content(),
+            "<start>\nNow: Three by Artist\n- Two\n<end>"
+        );
+
+        section.show(None, &mut edit);
+        assert_eq!(section.content(), "<start>\nNothing\n- Three\n<end>");
+    }
+
+    #[test]
+    fn content_is_cut_to_the_maximum_length() {
+        let original = "x".repeat(MAX_PROFILE_CONTENT_LENGTH - 30);
+        let mut section = section(Some(&original), 0);
+        section.current_track = Some(track(&"y".repeat(100)));
+        let content = section.content();
+
+        assert_eq!(content.chars().count(), MAX_PROFILE_CONTENT_LENGTH);
+        assert!(content.starts_with(&original));
+        assert!(content.ends_with("…\n<end>"));
+    }
+}
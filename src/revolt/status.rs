@@ -0,0 +1,215 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{config::RevoltStatusOptions, services::TrackInfo};
+
+/// The maximum status text length accepted by Revolt.
+pub const MAX_STATUS_LENGTH: usize = 128;
+
+/// The placeholders templates can contain.
+pub const PLACEHOLDERS: &[&str] = &["%ARTIST%", "%NAME%", "%ALBUM%"];
+
+/// Renders the status template for the given track, truncating it to fit
+/// into `max_length` characters.
+///
+/// The track name is shortened first, then the artist. If the status still
+/// does not fit, the rendered text itself is cut.
+pub fn render(options: &RevoltStatusOptions, track: &TrackInfo) -> String {
+    let max_length = options.max_length.min(MAX_STATUS_LENGTH);
+    let fill = |artist: &str, name: &str| {
+        fill_with(
+            &options.template,
+            artist,
+            name,
+            track.album.as_deref().unwrap_or_default(),
+        )
+    };
+
+    let status = fill(&track.artist, &track.name);
+    if fits(&status, max_length) {
+        return status;
+    }
+
+    let mut name = track.name.clone();
+    for shortened in shortened_variants(&track.name, &options.ellipsis) {
+        name = shortened;
+        let status = fill(&track.artist, &name);
+        if fits(&status, max_length) {
+            return status;
+        }
+    }
+
+    let mut artist = track.artist.clone();
+    for shortened in shortened_variants(&track.artist, &options.ellipsis) {
+        artist = shortened;
+        let status = fill(&artist, &name);
+        if fits(&status, max_length) {
+            return status;
+        }
+    }
+
+    truncate(&fill(&artist, &name), max_length, &options.ellipsis)
+}
+
+/// Replaces the placeholders in `template` with the track data.
+pub fn fill(template: &str, track: &TrackInfo) -> String {
+    fill_with(
+        template,
+        &track.artist,
+        &track.name,
+        track.album.as_deref().unwrap_or_default(),
+    )
+}
+
+fn fill_with(template: &str, artist: &str, name: &str, album: &str) -> String {
+    template
+        .replace("%ARTIST%", artist)
+        .replace("%NAME%", name)
+        .replace("%ALBUM%", album)
+}
+
+/// Cuts `text` at a grapheme boundary so that it, together with the
+/// ellipsis, is at most `max_length` characters long. If the ellipsis
+/// itself doesn't fit, or the cut text already ends with it, it's left out.
+pub fn truncate(text: &str, max_length: usize, ellipsis: &str) -> String {
+    if fits(text, max_length) {
+        return text.to_string();
+    }
+
+    let ellipsis = if fits(ellipsis, max_length.saturating_sub(1)) {
+        ellipsis
+    } else {
+        ""
+    };
+    let budget = max_length.saturating_sub(ellipsis.chars().count());
+    let mut length = 0;
+    let mut truncated = String::new();
+    for grapheme in text.graphemes(true) {
+        length += grapheme.chars().count();
+        if length > budget {
+            break;
+        }
+        truncated.push_str(grapheme);
+    }
+
+    let truncated = truncated.trim_end();
+    if !ellipsis.is_empty() && truncated.ends_with(ellipsis) {
+        return truncated.to_string();
+    }
+
+    format!("{truncated}{ellipsis}")
+}
+
+fn fits(text: &str, max_length: usize) -> bool {
+    text.chars().count() <= max_length
+}
+
+/// Yields `text` shortened by one more grapheme each time, with the
+/// ellipsis appended, down to a single grapheme.
+fn shortened_variants<'a>(text: &'a str, ellipsis: &'a str) -> impl Iterator<Item = String> + 'a {
+    let graphemes = text.graphemes(true).collect::<Vec<_>>();
+
+    (1..graphemes.len())
+        .rev()
+        .map(move |keep| format!("{}{ellipsis}", graphemes[..keep].concat().trim_end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(template: &str, max_length: usize, ellipsis: &str) -> RevoltStatusOptions {
+        RevoltStatusOptions {
+            template: template.to_string(),
+            max_length,
+            ellipsis: ellipsis.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn track(artist: &str, name: &str) -> TrackInfo {
+        TrackInfo {
+            artist: artist.to_string(),
+            name: name.to_string(),
+            album: None,
+            cover_art_url: None,
+        }
+    }
+
+    #[test]
+    fn truncate_keeps_short_text() {
+        assert_eq!(truncate("short", 10, "…"), "short");
+    }
+
+    #[test]
+    fn truncate_appends_ellipsis() {
+        assert_eq!(truncate("abcdefghij", 5, "…"), "abcd…");
+    }
+
+    #[test]
+    fn truncate_keeps_zwj_sequences_whole() {
+        // The family emoji is 5 characters joined into one grapheme.
+        let family = "👨\u{200d}👩\u{200d}👧";
+        let text = format!("ab{family}cd");
+
+        assert_eq!(truncate(&text, 6, "…"), "ab…");
+        assert_eq!(truncate(&text, 8, "…"), format!("ab{family}…"));
+    }
+
+    #[test]
+    fn truncate_keeps_combining_marks_with_their_base() {
+        // `e` and the combining acute accent are one grapheme.
+        let text = "cafe\u{301} noir";
+
+        assert_eq!(truncate(text, 5, "…"), "caf…");
+        assert_eq!(truncate(text, 6, "…"), "cafe\u{301}…");
+    }
+
+    #[test]
+    fn truncate_leaves_out_an_ellipsis_that_doesnt_fit() {
+        assert_eq!(truncate("abcdefghij", 3, "..."), "abc");
+        assert_eq!(truncate("abcdefghij", 2, "..."), "ab");
+    }
+
+    #[test]
+    fn truncate_doesnt_repeat_the_ellipsis() {
+        assert_eq!(truncate("ab… cd", 4, "…"), "ab…");
+        assert_eq!(truncate("abc... def", 9, "..."), "abc...");
+    }
+
+    #[test]
+    fn render_shortens_the_name_first() {
+        let options = options("%NAME% by %ARTIST%", 16, "…");
+
+        assert_eq!(
+            render(&options, &track("Artist", "A long name")),
+            "A lon… by Artist"
+        );
+    }
+
+    #[test]
+    fn render_shortens_the_artist_after_the_name() {
+        let options = options("%NAME% by %ARTIST%", 12, "…");
+
+        assert_eq!(
+            render(&options, &track("Long artist", "Name")),
+            "N… by Long…"
+        );
+    }
+
+    #[test]
+    fn render_truncates_the_shortened_status_last() {
+        let options = options("A fixed prefix: %NAME% by %ARTIST%", 20, "…");
+        let status = render(&options, &track("Artist", "Name"));
+
+        assert_eq!(status, "A fixed prefix: N…");
+        assert!(status.chars().count() <= 20);
+    }
+
+    #[test]
+    fn render_never_exceeds_revolts_limit() {
+        let options = options("%NAME%", 500, "…");
+        let status = render(&options, &track("Artist", &"x".repeat(300)));
+
+        assert_eq!(status.chars().count(), MAX_STATUS_LENGTH);
+    }
+}
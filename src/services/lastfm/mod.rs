@@ -4,10 +4,7 @@ mod models;
 
 use reqwest::{ClientBuilder, StatusCode, Url};
 use serde::Deserialize;
-use tokio::{
-    sync::mpsc::Sender,
-    time::{interval, Duration},
-};
+use tokio::{sync::mpsc::Sender, task::JoinHandle, time::interval};
 use tracing::{error, trace};
 
 use crate::{cli::start::ChannelData, config::LastFMServiceOptions};
@@ -29,7 +26,15 @@ impl LastFMCompatibleServiceProvider for LastFM {
     const API_URL: &'static str = "http://ws.audioscrobbler.com/2.0/";
 }
 
-impl LastFM {
+impl ServiceProvider for LastFM {
+    fn initialise(&mut self) -> anyhow::Result<&Self> {
+        trace!("initialising self fields");
+        self.http_client = ClientBuilder::new().user_agent(Self::USER_AGENT).build()?;
+        trace!("initialised self fields");
+
+        Ok(self)
+    }
+
     async fn get_current_playing_track(&self) -> anyhow::Result<Option<TrackInfo>> {
         let url = Url::parse_with_params(
             Self::API_URL,
@@ -62,6 +67,18 @@ impl LastFM {
                         return Ok(Some(TrackInfo {
                             artist: track.artist.text.clone(),
                             name: track.name.clone(),
+                            album: track
+                                .album
+                                .as_ref()
+                                .map(|album| album.text.clone())
+                                .filter(|album| !album.is_empty()),
+                            // Images are sorted from the smallest to the largest.
+                            cover_art_url: track
+                                .image
+                                .iter()
+                                .rev()
+                                .map(|image| image.url.clone())
+                                .find(|url| !url.is_empty()),
                         }));
                     }
                 }
@@ -82,21 +99,15 @@ impl LastFM {
 
         Ok(None)
     }
-}
 
-impl ServiceProvider for LastFM {
-    fn initialise(&mut self) -> anyhow::Result<&Self> {
-        trace!("initialising self fields");
-        self.http_client = ClientBuilder::new().user_agent(Self::USER_AGENT).build()?;
-        trace!("initialised self fields");
-
-        Ok(self)
+    async fn verify(&self) -> anyhow::Result<()> {
+        self.get_current_playing_track().await.map(|_| ())
     }
 
-    fn track_check_loop(self, tx: Sender<ChannelData>) {
+    fn track_check_loop(self, tx: Sender<ChannelData>) -> JoinHandle<anyhow::Result<()>> {
         trace!("spawning task for `track_check_loop`");
-        tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(self.options.check_interval.into()));
+        let task = tokio::spawn(async move {
+            let mut interval = interval(self.options.check_interval.as_duration());
 
             trace!("looping `track_check_loop`");
             loop {
@@ -119,6 +130,8 @@ impl ServiceProvider for LastFM {
             Ok::<_, anyhow::Error>(())
         });
         trace!("spawned task for `track_check_loop`");
+
+        task
     }
 }
 
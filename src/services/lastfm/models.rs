@@ -16,6 +16,9 @@ pub mod user {
         pub struct Track {
             pub artist: Artist,
             pub name: String,
+            pub album: Option<Album>,
+            #[serde(default)]
+            pub image: Vec<Image>,
             #[serde(rename = "@attr")]
             pub attr: Option<TrackAttr>,
         }
@@ -26,6 +29,18 @@ pub mod user {
             pub text: String,
         }
 
+        #[derive(Deserialize, Debug)]
+        pub struct Album {
+            #[serde(rename = "#text")]
+            pub text: String,
+        }
+
+        #[derive(Deserialize, Debug)]
+        pub struct Image {
+            #[serde(rename = "#text")]
+            pub url: String,
+        }
+
         #[derive(Deserialize, Debug)]
         pub struct TrackAttr {
             pub nowplaying: Option<String>,
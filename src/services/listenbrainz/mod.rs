@@ -1,10 +1,7 @@
 #![cfg(feature = "services-listenbrainz")]
 
 use reqwest::StatusCode;
-use tokio::{
-    sync::mpsc,
-    time::{interval, Duration},
-};
+use tokio::{sync::mpsc, task::JoinHandle, time::interval};
 use tracing::{error, trace};
 
 use crate::{cli::start::ChannelData, config::ListenBrainzServiceOptions};
@@ -13,13 +10,19 @@ use super::{ServiceProvider, TrackInfo};
 
 mod models;
 
+const COVER_ART_ARCHIVE_URL: &str = "https://coverartarchive.org";
+
 #[derive(Default, Debug)]
 pub struct ListenBrainz {
     pub http_client: reqwest::Client,
     pub options: ListenBrainzServiceOptions,
 }
 
-impl ListenBrainz {
+impl ServiceProvider for ListenBrainz {
+    fn initialise(&mut self) -> anyhow::Result<&Self> {
+        Ok(self)
+    }
+
     async fn get_current_playing_track(&self) -> anyhow::Result<Option<TrackInfo>> {
         let url = format!(
             "{}/1/user/{}/playing-now",
@@ -42,6 +45,17 @@ impl ListenBrainz {
                         return Ok(Some(TrackInfo {
                             artist: track.track_metadata.artist_name.clone(),
                             name: track.track_metadata.track_name.clone(),
+                            album: track.track_metadata.release_name.clone(),
+                            cover_art_url: track
+                                .track_metadata
+                                .additional_info
+                                .as_ref()
+                                .and_then(|info| info.release_mbid.as_ref())
+                                .map(|release_mbid| {
+                                    format!(
+                                        "{COVER_ART_ARCHIVE_URL}/release/{release_mbid}/front-500"
+                                    )
+                                }),
                         }));
                     }
                 }
@@ -56,17 +70,36 @@ impl ListenBrainz {
 
         Ok(None)
     }
-}
 
-impl ServiceProvider for ListenBrainz {
-    fn initialise(&mut self) -> anyhow::Result<&Self> {
-        Ok(self)
+    async fn verify(&self) -> anyhow::Result<()> {
+        let url = format!(
+            "{}/1/user/{}/playing-now",
+            self.options.api_url, &self.options.username
+        );
+
+        match self
+            .http_client
+            .get(url)
+            .send()
+            .await?
+            .handle_user_friendly_error()
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(LastFMError::APIError(LastFMAPIError::NotFound())) => {
+                anyhow::bail!("User `{}` not found.", self.options.username)
+            }
+            Err(error) => Err(error.into()),
+        }
     }
 
-    fn track_check_loop(self, tx: mpsc::Sender<crate::cli::start::ChannelData>) {
+    fn track_check_loop(
+        self,
+        tx: mpsc::Sender<crate::cli::start::ChannelData>,
+    ) -> JoinHandle<anyhow::Result<()>> {
         trace!("spawning task for `track_check_loop`");
-        tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(self.options.check_interval.into()));
+        let task = tokio::spawn(async move {
+            let mut interval = interval(self.options.check_interval.as_duration());
 
             trace!("looping `track_check_loop`");
             loop {
@@ -89,6 +122,8 @@ impl ServiceProvider for ListenBrainz {
             Ok::<_, anyhow::Error>(())
         });
         trace!("spawned task for `track_check_loop`");
+
+        task
     }
 }
 
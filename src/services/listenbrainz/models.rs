@@ -22,6 +22,13 @@ pub mod user {
         pub struct TrackMetadata {
             pub artist_name: String,
             pub track_name: String,
+            pub release_name: Option<String>,
+            pub additional_info: Option<AdditionalInfo>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        pub struct AdditionalInfo {
+            pub release_mbid: Option<String>,
         }
     }
 }
@@ -1,21 +1,106 @@
 #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
-use crate::cli::start::ChannelData;
+use crate::{
+    cli::start::ChannelData,
+    config::{self, Config},
+};
 
 #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
-use tokio::sync::mpsc::Sender;
+use serde::Serialize;
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+use tokio::{sync::mpsc::Sender, task::JoinHandle};
 
 pub mod lastfm;
 pub mod listenbrainz;
 
 #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct TrackInfo {
     pub artist: String,
     pub name: String,
+    pub album: Option<String>,
+    pub cover_art_url: Option<String>,
 }
 
 #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
 pub trait ServiceProvider: Sized {
     fn initialise(&mut self) -> anyhow::Result<&Self>;
-    fn track_check_loop(self, tx: Sender<ChannelData>);
+    /// Checks that the configured user exists and the credentials are
+    /// accepted by the service.
+    async fn verify(&self) -> anyhow::Result<()>;
+    async fn get_current_playing_track(&self) -> anyhow::Result<Option<TrackInfo>>;
+    fn track_check_loop(self, tx: Sender<ChannelData>) -> JoinHandle<anyhow::Result<()>>;
+}
+
+/// The enabled service, built from the configuration. New services only
+/// have to be added here to be usable by every command.
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+pub enum Service {
+    #[cfg(feature = "services-lastfm")]
+    LastFM(lastfm::LastFM),
+    #[cfg(feature = "services-listenbrainz")]
+    ListenBrainz(listenbrainz::ListenBrainz),
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+impl Service {
+    /// Builds and initialises `enabled_service` with its options in
+    /// `config`.
+    pub fn try_new(config: &Config, enabled_service: &config::Services) -> anyhow::Result<Self> {
+        match enabled_service {
+            #[cfg(feature = "services-lastfm")]
+            config::Services::LastFM => {
+                #[cfg(all(feature = "services-lastfm", feature = "services-listenbrainz"))]
+                let Some(options) = &config.services.lastfm
+                else {
+                    anyhow::bail!("Last.fm is enabled, but no configuration is provided.")
+                };
+                #[cfg(all(feature = "services-lastfm", not(feature = "services-listenbrainz")))]
+                let options = &config.services.lastfm;
+
+                let mut service = lastfm::LastFM {
+                    options: options.clone(),
+                    ..Default::default()
+                };
+
+                service.initialise()?;
+                Ok(Self::LastFM(service))
+            }
+            #[cfg(feature = "services-listenbrainz")]
+            config::Services::ListenBrainz => {
+                #[cfg(all(feature = "services-lastfm", feature = "services-listenbrainz"))]
+                let Some(options) = &config.services.listenbrainz
+                else {
+                    anyhow::bail!("ListenBrainz is enabled, but no configuration is provided.")
+                };
+                #[cfg(all(feature = "services-listenbrainz", not(feature = "services-lastfm")))]
+                let options = &config.services.listenbrainz;
+
+                let mut service = listenbrainz::ListenBrainz {
+                    options: options.clone(),
+                    ..Default::default()
+                };
+
+                service.initialise()?;
+                Ok(Self::ListenBrainz(service))
+            }
+        }
+    }
+
+    pub async fn get_current_playing_track(&self) -> anyhow::Result<Option<TrackInfo>> {
+        match self {
+            #[cfg(feature = "services-lastfm")]
+            Self::LastFM(service) => service.get_current_playing_track().await,
+            #[cfg(feature = "services-listenbrainz")]
+            Self::ListenBrainz(service) => service.get_current_playing_track().await,
+        }
+    }
+
+    pub fn track_check_loop(self, tx: Sender<ChannelData>) -> JoinHandle<anyhow::Result<()>> {
+        match self {
+            #[cfg(feature = "services-lastfm")]
+            Self::LastFM(service) => service.track_check_loop(tx),
+            #[cfg(feature = "services-listenbrainz")]
+            Self::ListenBrainz(service) => service.track_check_loop(tx),
+        }
+    }
 }
@@ -0,0 +1,270 @@
+//! Config file formats other than YAML, with the same operations as
+//! [`super::yaml`].
+
+use std::{ffi::OsStr, path::Path};
+
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+
+use super::yaml;
+
+/// The format of a config file.
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    #[default]
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// Detects the format from the extension of `path`, defaulting to YAML.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(OsStr::to_str) {
+            Some("toml") => Self::Toml,
+            Some("json") => Self::Json,
+            _ => Self::Yaml,
+        }
+    }
+
+    /// Converts a YAML document to this format. Comments are only kept for
+    /// YAML, and TOML has no null, so null values, and the tables left
+    /// empty without them, are left out of it.
+    pub fn convert_yaml(self, document: &str) -> anyhow::Result<String> {
+        if self == Self::Yaml {
+            return Ok(document.to_string());
+        }
+
+        let value = serde_yaml::from_str::<Value>(document)?;
+
+        Ok(match self {
+            Self::Yaml => unreachable!("YAML is returned as is"),
+            Self::Toml => toml_edit::ser::to_string_pretty(&without_nulls(value))?,
+            Self::Json => format!("{}\n", serde_json::to_string_pretty(&value)?),
+        })
+    }
+
+    /// Sets the value at `path` of a document, creating the missing tables.
+    /// `None` removes the key.
+    pub fn set_value(
+        self,
+        document: &str,
+        path: &[&str],
+        value: Option<&str>,
+    ) -> anyhow::Result<String> {
+        let (last_key, parent_keys) = path.split_last().expect("path is never empty");
+
+        match self {
+            Self::Yaml => Ok(yaml::set_value(document, path, value)),
+            Self::Toml => {
+                let mut root = document.parse::<toml_edit::DocumentMut>()?;
+                let mut table: &mut dyn toml_edit::TableLike = root.as_table_mut();
+                for key in parent_keys {
+                    table = table
+                        .entry(key)
+                        .or_insert_with(implicit_table)
+                        .as_table_like_mut()
+                        .ok_or_else(|| anyhow::anyhow!("`{key}` is not a table."))?;
+                }
+
+                match value {
+                    // Replacing the item rather than the entry keeps the
+                    // comments above the key.
+                    Some(value) => match table.get_mut(last_key) {
+                        Some(item) => *item = toml_edit::value(value),
+                        None => {
+                            table.insert(last_key, toml_edit::value(value));
+                        }
+                    },
+                    None => {
+                        table.remove(last_key);
+                    }
+                }
+
+                Ok(root.to_string())
+            }
+            Self::Json => {
+                let mut root = if document.trim().is_empty() {
+                    Value::Object(Map::new())
+                } else {
+                    serde_json::from_str(document)?
+                };
+                let mut object = &mut root;
+                for key in parent_keys {
+                    let child = object
+                        .as_object_mut()
+                        .ok_or_else(|| anyhow::anyhow!("`{key}` is not in an object."))?
+                        .entry(*key)
+                        .or_insert(Value::Null);
+                    if child.is_null() {
+                        *child = Value::Object(Map::new());
+                    }
+
+                    object = child;
+                }
+
+                let object = object
+                    .as_object_mut()
+                    .ok_or_else(|| anyhow::anyhow!("`{last_key}` is not in an object."))?;
+                match value {
+                    Some(value) => {
+                        object.insert((*last_key).to_string(), Value::String(value.to_string()));
+                    }
+                    None => {
+                        object.remove(*last_key);
+                    }
+                }
+
+                Ok(format!("{}\n", serde_json::to_string_pretty(&root)?))
+            }
+        }
+    }
+}
+
+/// A table that is only written as a header if it has values of its own.
+fn implicit_table() -> toml_edit::Item {
+    let mut table = toml_edit::Table::new();
+    table.set_implicit(true);
+
+    toml_edit::Item::Table(table)
+}
+
+/// Removes null values, and objects that only contained null values.
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, without_nulls(value)))
+                .filter(|(_, value)| match value {
+                    Value::Null => false,
+                    Value::Object(map) => !map.is_empty(),
+                    _ => true,
+                })
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+#[cfg(all(test, feature = "services-lastfm", feature = "services-listenbrainz"))]
+mod tests {
+    use figment::{
+        providers::{Format, Json, Toml, Yaml},
+        Figment,
+    };
+
+    use super::*;
+    use crate::config::Config;
+
+    const SAMPLE: &str = include_str!("../../resources/config.sample.yaml");
+
+    /// Generates the sample config in `format`, fills in the values it
+    /// leaves empty, and loads it.
+    fn load_sample(format: ConfigFormat) -> Value {
+        let mut document = format.convert_yaml(SAMPLE).unwrap();
+        for (path, value) in [
+            (&["services", "lastfm", "username"][..], "me"),
+            (&["services", "lastfm", "api_key"], "key"),
+            (&["services", "listenbrainz", "username"], "me"),
+            (&["revolt", "session_token"], "token"),
+        ] {
+            document = format.set_value(&document, path, Some(value)).unwrap();
+        }
+
+        let figment = match format {
+            ConfigFormat::Yaml => Figment::from(Yaml::string(&document)),
+            ConfigFormat::Toml => Figment::from(Toml::string(&document)),
+            ConfigFormat::Json => Figment::from(Json::string(&document)),
+        };
+        let config = figment
+            .extract::<Config>()
+            .unwrap_or_else(|error| panic!("{format:?} sample does not load: {error}"));
+
+        serde_json::to_value(config).unwrap()
+    }
+
+    #[test]
+    fn generated_samples_load_the_same_config() {
+        let yaml = load_sample(ConfigFormat::Yaml);
+
+        assert_eq!(load_sample(ConfigFormat::Toml), yaml);
+        assert_eq!(load_sample(ConfigFormat::Json), yaml);
+    }
+
+    #[test]
+    fn toml_leaves_out_nulls_and_empty_tables() {
+        let document = ConfigFormat::Toml
+            .convert_yaml(
+                "revolt:\n  api_url: x\n  bot_token:\n  status:\n    presence:\n      idle:\n",
+            )
+            .unwrap();
+
+        assert_eq!(document, "[revolt]\napi_url = \"x\"\n");
+    }
+
+    #[test]
+    fn json_keeps_nulls() {
+        let document = ConfigFormat::Json
+            .convert_yaml("revolt:\n  bot_token:\n")
+            .unwrap();
+
+        assert_eq!(
+            document,
+            "{\n  \"revolt\": {\n    \"bot_token\": null\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn toml_set_value_creates_tables_and_keeps_comments() {
+        let document = "# lure\n[revolt]\n# the token\nsession_token = \"old\"\n";
+
+        assert_eq!(
+            ConfigFormat::Toml
+                .set_value(document, &["revolt", "session_token"], Some("new"))
+                .unwrap(),
+            "# lure\n[revolt]\n# the token\nsession_token = \"new\"\n"
+        );
+        assert_eq!(
+            ConfigFormat::Toml
+                .set_value("", &["revolt", "status", "idle"], Some("x"))
+                .unwrap(),
+            "[revolt.status]\nidle = \"x\"\n"
+        );
+        assert_eq!(
+            ConfigFormat::Toml
+                .set_value(document, &["revolt", "session_token"], None)
+                .unwrap(),
+            "# lure\n[revolt]\n"
+        );
+    }
+
+    #[test]
+    fn json_set_value_creates_objects() {
+        assert_eq!(
+            ConfigFormat::Json
+                .set_value("", &["revolt", "api_url"], Some("x"))
+                .unwrap(),
+            "{\n  \"revolt\": {\n    \"api_url\": \"x\"\n  }\n}\n"
+        );
+        assert_eq!(
+            ConfigFormat::Json
+                .set_value(
+                    "{\"revolt\": {\"api_url\": \"x\", \"bot_token\": null}}",
+                    &["revolt", "bot_token"],
+                    None
+                )
+                .unwrap(),
+            "{\n  \"revolt\": {\n    \"api_url\": \"x\"\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn set_value_rejects_values_in_place_of_tables() {
+        assert!(ConfigFormat::Toml
+            .set_value("revolt = 1\n", &["revolt", "api_url"], Some("x"))
+            .is_err());
+        assert!(ConfigFormat::Json
+            .set_value("{\"revolt\": 1}", &["revolt", "api_url"], Some("x"))
+            .is_err());
+    }
+}
@@ -1 +1,3 @@
+pub mod format;
 pub mod log;
+pub mod yaml;
@@ -0,0 +1,313 @@
+//! Minimal line based YAML editing that keeps comments and layout intact.
+//!
+//! Only block mappings are walked, which is enough for lure's config
+//! files. Other values, such as block scalars and sequences, are replaced
+//! as a whole.
+
+/// Sets the value at `path` of a YAML document, creating the missing
+/// mappings. The whole old value is replaced, even if it spans several
+/// lines. `None` removes the key.
+pub fn set_value(document: &str, path: &[&str], value: Option<&str>) -> String {
+    let mut lines = document.lines().map(String::from).collect::<Vec<_>>();
+    let (mut start, mut end) = (0, lines.len());
+    let mut parent_indentation = None;
+
+    for (depth, key) in path.iter().enumerate() {
+        let is_last = depth == path.len() - 1;
+
+        if let Some(index) = find_key(&lines[start..end], key) {
+            let index = start + index;
+            let indentation = indentation(&lines[index]);
+
+            if is_last {
+                let value_end = value_end(&lines, index, indentation);
+                let new_lines = value.map(|value| key_line(indentation, key, Some(value)));
+                lines.splice(index..value_end, new_lines);
+            } else {
+                parent_indentation = Some(indentation);
+                start = index + 1;
+                end = block_end(&lines, start, indentation);
+            }
+
+            continue;
+        }
+
+        if value.is_none() {
+            break;
+        }
+
+        let indentation = lines[start..end]
+            .iter()
+            .find(|line| is_content(line))
+            .map_or_else(
+                || parent_indentation.map_or(0, |indentation| indentation + 2),
+                |line| indentation(line),
+            );
+        let insert_at = lines[start..end]
+            .iter()
+            .rposition(|line| is_content(line))
+            .map_or(start, |index| start + index + 1);
+
+        let new_lines = path[depth..].iter().enumerate().map(|(offset, key)| {
+            if depth + offset == path.len() - 1 {
+                key_line(indentation + offset * 2, key, value)
+            } else {
+                key_line(indentation + offset * 2, key, None)
+            }
+        });
+        lines.splice(insert_at..insert_at, new_lines.collect::<Vec<_>>());
+
+        break;
+    }
+
+    let mut document_out = lines.join("\n");
+    if document.ends_with('\n') || document.is_empty() {
+        document_out.push('\n');
+    }
+
+    document_out
+}
+
+/// Renders `value` as a YAML document, adding the comment returned by
+/// `comment` for a dotted key after its value.
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+pub fn to_string(value: &serde_json::Value, comment: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut document = String::new();
+
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                write_entry(&mut document, 0, key, key, value, comment);
+            }
+        }
+        value => document.push_str(&format!("{}\n", scalar(value))),
+    }
+
+    document
+}
+
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+fn write_entry(
+    document: &mut String,
+    indentation: usize,
+    path: &str,
+    key: &str,
+    value: &serde_json::Value,
+    comment: &dyn Fn(&str) -> Option<String>,
+) {
+    let padding = " ".repeat(indentation);
+
+    match value {
+        serde_json::Value::Object(map) if !map.is_empty() => {
+            document.push_str(&format!("{padding}{key}:\n"));
+            for (child_key, value) in map {
+                let child_path = format!("{path}.{child_key}");
+                write_entry(
+                    document,
+                    indentation + 2,
+                    &child_path,
+                    child_key,
+                    value,
+                    comment,
+                );
+            }
+        }
+        value => {
+            document.push_str(&format!("{padding}{key}: {}", scalar(value)));
+            if let Some(comment) = comment(path) {
+                document.push_str(&format!(" # {comment}"));
+            }
+            document.push('\n');
+        }
+    }
+}
+
+/// JSON values are valid YAML flow values.
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+fn scalar(value: &serde_json::Value) -> String {
+    serde_json::to_string(value).expect("JSON values always serialise")
+}
+
+/// Returns the 1-based line number of the key at `path`, or of its closest
+/// parent if the key itself is missing.
+#[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+pub fn line_of(document: &str, path: &[&str]) -> Option<usize> {
+    let lines = document.lines().map(String::from).collect::<Vec<_>>();
+    let (mut start, mut end) = (0, lines.len());
+    let mut line = None;
+
+    for key in path {
+        let Some(index) = find_key(&lines[start..end], key) else {
+            break;
+        };
+        let index = start + index;
+
+        line = Some(index + 1);
+        start = index + 1;
+        end = block_end(&lines, start, indentation(&lines[index]));
+    }
+
+    line
+}
+
+fn find_key(block: &[String], key: &str) -> Option<usize> {
+    let child_indentation = block
+        .iter()
+        .find(|line| is_content(line))
+        .map(|line| indentation(line))?;
+
+    block.iter().position(|line| {
+        is_content(line)
+            && indentation(line) == child_indentation
+            && line.trim_start().strip_prefix(key).is_some_and(|rest| {
+                rest.strip_prefix(':')
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+            })
+    })
+}
+
+/// Returns the end of the block under a key with `parent_indentation`,
+/// which starts at `start`. A sequence may be as indented as its key.
+fn block_end(lines: &[String], start: usize, parent_indentation: usize) -> usize {
+    lines[start..]
+        .iter()
+        .position(|line| {
+            is_content(line)
+                && (indentation(line) < parent_indentation
+                    || indentation(line) == parent_indentation
+                        && !line.trim_start().starts_with('-'))
+        })
+        .map_or(lines.len(), |index| start + index)
+}
+
+/// Returns the end of the key at `index` and its value, such as a block
+/// scalar or a nested mapping. Blank and comment lines after the value are
+/// kept, since they belong to the next key.
+fn value_end(lines: &[String], index: usize, indentation: usize) -> usize {
+    let end = block_end(lines, index + 1, indentation);
+
+    lines[index + 1..end]
+        .iter()
+        .rposition(|line| is_content(line))
+        .map_or(index + 1, |last| index + 1 + last + 1)
+}
+
+fn key_line(indentation: usize, key: &str, value: Option<&str>) -> String {
+    let padding = " ".repeat(indentation);
+
+    value.map_or_else(
+        || format!("{padding}{key}:"),
+        |value| {
+            // JSON strings are valid double quoted YAML strings.
+            let value = serde_json::to_string(value).expect("strings always serialise");
+            format!("{padding}{key}: {value}")
+        },
+    )
+}
+
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim_start();
+
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_value_replaces_a_scalar_and_keeps_comments() {
+        let document = "# lure\nrevolt:\n  # the token\n  session_token: old\n  api_url: x\n";
+
+        assert_eq!(
+            set_value(document, &["revolt", "session_token"], Some("new")),
+            "# lure\nrevolt:\n  # the token\n  session_token: \"new\"\n  api_url: x\n"
+        );
+    }
+
+    #[test]
+    fn set_value_creates_missing_mappings() {
+        assert_eq!(
+            set_value(
+                "enable: lastfm\n",
+                &["revolt", "status", "template"],
+                Some("%NAME%")
+            ),
+            "enable: lastfm\nrevolt:\n  status:\n    template: \"%NAME%\"\n"
+        );
+        assert_eq!(
+            set_value("", &["revolt", "api_url"], Some("x")),
+            "revolt:\n  api_url: \"x\"\n"
+        );
+    }
+
+    #[test]
+    fn set_value_replaces_a_block_scalar() {
+        let document =
+            "revolt:\n  session_token: |\n    line one\n\n    line two\n  # next\n  api_url: x\n";
+
+        assert_eq!(
+            set_value(document, &["revolt", "session_token"], Some("new")),
+            "revolt:\n  session_token: \"new\"\n  # next\n  api_url: x\n"
+        );
+    }
+
+    #[test]
+    fn set_value_replaces_a_mapping_and_a_sequence() {
+        let document = "revolt:\n  status:\n    template: x\n    idle: y\n  api_url: x\n";
+        assert_eq!(
+            set_value(document, &["revolt", "status"], Some("z")),
+            "revolt:\n  status: \"z\"\n  api_url: x\n"
+        );
+
+        let document = "list:\n- a\n- b\nnext: x\n";
+        assert_eq!(
+            set_value(document, &["list"], Some("z")),
+            "list: \"z\"\nnext: x\n"
+        );
+    }
+
+    #[test]
+    fn set_value_removes_the_key_when_unset() {
+        let document = "revolt:\n  session_token: !age |\n    -----BEGIN AGE ENCRYPTED FILE-----\n  api_url: x\n";
+
+        assert_eq!(
+            set_value(document, &["revolt", "session_token"], None),
+            "revolt:\n  api_url: x\n"
+        );
+        assert_eq!(
+            set_value(document, &["revolt", "bot_token"], None),
+            document
+        );
+    }
+
+    #[test]
+    fn set_value_only_matches_whole_keys_at_the_same_level() {
+        let document = "revolt:\n  session_token_file: a\n  status:\n    session_token: b\n";
+
+        assert_eq!(
+            set_value(document, &["revolt", "session_token"], Some("c")),
+            "revolt:\n  session_token_file: a\n  status:\n    session_token: b\n  session_token: \"c\"\n"
+        );
+    }
+
+    #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+    const COMMENTED: &str = "# lure\nrevolt:\n  # The token.\n  #\n  session_token: x\n  status:\n    idle: y\nservices: {}\n";
+
+    #[cfg(any(feature = "services-lastfm", feature = "services-listenbrainz"))]
+    #[test]
+    fn line_of_finds_nested_keys_or_their_parent() {
+        assert_eq!(line_of(COMMENTED, &["revolt", "session_token"]), Some(5));
+        assert_eq!(line_of(COMMENTED, &["revolt", "status", "idle"]), Some(7));
+        assert_eq!(
+            line_of(COMMENTED, &["revolt", "status", "template"]),
+            Some(6)
+        );
+        assert_eq!(line_of(COMMENTED, &["services"]), Some(8));
+        assert_eq!(line_of(COMMENTED, &["enable"]), None);
+    }
+}